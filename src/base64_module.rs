@@ -0,0 +1,138 @@
+use std::{error::Error, fmt};
+
+// Valeur de la table inverse pour un caractère hors de l'alphabet
+const INVALID: u8 = 0xFF;
+
+// Alphabet base64 : 64 symboles et la table inverse caractère -> valeur,
+// calculée une seule fois à la compilation pour un décodage en O(1) par caractère
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: [u8; 64],
+    values: [u8; 256],
+}
+
+impl Alphabet {
+    // Les symboles doivent être 64 caractères ASCII distincts
+    pub const fn new(symbols: &[u8; 64]) -> Self {
+        let mut values = [INVALID; 256];
+        let mut index = 0;
+        while index < 64 {
+            let symbol = symbols[index];
+            assert!(symbol.is_ascii() && symbol != b'=', "Symbole base64 invalide");
+            assert!(values[symbol as usize] == INVALID, "Symbole base64 en double");
+            values[symbol as usize] = index as u8;
+            index += 1;
+        }
+        Alphabet { symbols: *symbols, values }
+    }
+
+    pub fn symbol(&self, value: u8) -> char {
+        self.symbols[(value & 0x3F) as usize] as char
+    }
+
+    pub fn value(&self, symbol: char) -> Option<u8> {
+        if !symbol.is_ascii() {
+            return None;
+        }
+        match self.values[symbol as usize] {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+}
+
+// Alphabet de la RFC 4648
+pub const STANDARD: Alphabet =
+    Alphabet::new(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
+
+// Alphabet du serveur de jeu : les minuscules avant les majuscules
+pub const GAME: Alphabet =
+    Alphabet::new(b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base64Error {
+    // Une chaîne de 4n + 1 caractères ne peut pas coder un nombre entier d'octets
+    InvalidLength(usize),
+    InvalidCharacter { character: char, position: usize },
+    // Les bits inutilisés du dernier caractère doivent être nuls
+    NonCanonicalTrailingBits { character: char },
+}
+
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base64Error::InvalidLength(length) => {
+                write!(f, "longueur incorrecte ({} caractères)", length)
+            }
+            Base64Error::InvalidCharacter { character, position } => {
+                write!(f, "caractère invalide {:?} en position {}", character, position)
+            }
+            Base64Error::NonCanonicalTrailingBits { character } => {
+                write!(f, "bits de fin non nuls dans le dernier caractère {:?}", character)
+            }
+        }
+    }
+}
+
+impl Error for Base64Error {}
+
+// Encoder sans caractères de padding, comme le fait le serveur
+pub fn encode(bytes: &[u8], alphabet: &Alphabet) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        // Regrouper jusqu'à 3 octets en un bloc de 24 bits
+        let block = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |block, (index, byte)| block | (*byte as u32) << (16 - 8 * index));
+        // 1 octet -> 2 caractères, 2 octets -> 3 caractères, 3 octets -> 4 caractères
+        for index in 0..=chunk.len() {
+            encoded.push(alphabet.symbol((block >> (18 - 6 * index)) as u8));
+        }
+    }
+    encoded
+}
+
+// Décoder une chaîne avec ou sans padding '='. Les espaces autour de la chaîne sont ignorés,
+// et une chaîne dont les bits inutilisés ne sont pas nuls est refusée : chaque suite
+// d'octets n'a ainsi qu'un seul encodage accepté.
+pub fn decode(encoded: &str, alphabet: &Alphabet) -> Result<Vec<u8>, Base64Error> {
+    let encoded = encoded.trim();
+    let unpadded = match encoded.len() % 4 {
+        0 if encoded.ends_with("==") => &encoded[..encoded.len() - 2],
+        0 if encoded.ends_with('=') => &encoded[..encoded.len() - 1],
+        _ => encoded,
+    };
+    if unpadded.len() % 4 == 1 {
+        return Err(Base64Error::InvalidLength(encoded.chars().count()));
+    }
+
+    let mut decoded = Vec::with_capacity(unpadded.len() * 3 / 4);
+    let mut block = 0u32;
+    let mut symbols = 0;
+    for (position, character) in unpadded.chars().enumerate() {
+        let value = alphabet
+            .value(character)
+            .ok_or(Base64Error::InvalidCharacter { character, position })?;
+        block = block << 6 | value as u32;
+        symbols += 1;
+        if symbols == 4 {
+            decoded.extend_from_slice(&block.to_be_bytes()[1..]);
+            block = 0;
+            symbols = 0;
+        }
+    }
+
+    // Bloc incomplet : 2 caractères -> 1 octet (4 bits inutilisés), 3 caractères -> 2 octets (2 bits)
+    if symbols > 0 {
+        let unused_bits = 6 * symbols % 8;
+        if block & ((1 << unused_bits) - 1) != 0 {
+            let character = unpadded.chars().last().unwrap_or_default();
+            return Err(Base64Error::NonCanonicalTrailingBits { character });
+        }
+        let block = block >> unused_bits;
+        let bytes = symbols - 1;
+        decoded.extend_from_slice(&block.to_be_bytes()[4 - bytes..]);
+    }
+    Ok(decoded)
+}
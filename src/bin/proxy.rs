@@ -0,0 +1,325 @@
+// Proxy de débogage entre un client et le serveur de jeu.
+// Chaque trame est décodée avec les types du protocole et affichée, et des règles
+// permettent de retarder, supprimer ou modifier certaines trames.
+use std::{
+    env,
+    net::{Shutdown, TcpListener, TcpStream},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use sauve_qui_peut::communication_module::{is_connection_closed, FrameCodec, FrameDirection};
+use sauve_qui_peut::player_module::decoder;
+use sauve_qui_peut::protocol_module::{ClientMessage, ServerMessage};
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:8779";
+const DEFAULT_SERVER: &str = "localhost:8778";
+
+fn usage() -> &'static str {
+    "Usage : proxy [--listen hôte:port] [--server hôte:port] [--rule sens:motif:action]...\n\
+     \x20  sens   : in (serveur -> client), out (client -> serveur) ou any\n\
+     \x20  motif  : texte recherché dans le JSON brut de la trame, * pour toutes les trames\n\
+     \x20  action : delay=ms, drop ou replace=avant=>après"
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RuleAction {
+    Delay(Duration),
+    Drop,
+    Replace { from: String, to: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    direction: Option<FrameDirection>,  // None : les deux sens
+    pattern: String,
+    action: RuleAction,
+}
+
+impl Rule {
+    // Format "sens:motif:action", par exemple "in:RadarView:delay=500" ou "out:MoveTo:drop"
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = text.splitn(3, ':');
+        let (Some(direction), Some(pattern), Some(action)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(format!("Règle invalide : {}", text));
+        };
+        let direction = match direction {
+            "in" => Some(FrameDirection::Inbound),
+            "out" => Some(FrameDirection::Outbound),
+            "any" => None,
+            _ => return Err(format!("Sens invalide dans la règle {} : {}", text, direction)),
+        };
+        let action = if action == "drop" {
+            RuleAction::Drop
+        } else if let Some(millis) = action.strip_prefix("delay=") {
+            let millis = millis
+                .parse()
+                .map_err(|_| format!("Délai invalide dans la règle {} : {}", text, millis))?;
+            RuleAction::Delay(Duration::from_millis(millis))
+        } else if let Some(replacement) = action.strip_prefix("replace=") {
+            let (from, to) = replacement
+                .split_once("=>")
+                .ok_or_else(|| format!("Remplacement invalide dans la règle {} (attendu avant=>après)", text))?;
+            RuleAction::Replace { from: from.to_string(), to: to.to_string() }
+        } else {
+            return Err(format!("Action invalide dans la règle {} : {}", text, action));
+        };
+        Ok(Rule { direction, pattern: pattern.to_string(), action })
+    }
+
+    fn matches(&self, direction: FrameDirection, frame: &str) -> bool {
+        self.direction.is_none_or(|expected| expected == direction)
+            && (self.pattern == "*" || frame.contains(&self.pattern))
+    }
+}
+
+struct ProxyConfig {
+    listen: String,
+    server: String,
+    rules: Vec<Rule>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<ProxyConfig, String> {
+    let mut config = ProxyConfig {
+        listen: DEFAULT_LISTEN.to_string(),
+        server: DEFAULT_SERVER.to_string(),
+        rules: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("Valeur manquante pour {}", arg));
+        match arg.as_str() {
+            "--listen" => config.listen = value()?,
+            "--server" => config.server = value()?,
+            "--rule" => config.rules.push(Rule::parse(&value()?)?),
+            _ => return Err(format!("Argument inattendu : {}", arg)),
+        }
+    }
+    Ok(config)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", usage());
+        return;
+    }
+    let config = match parse_args(args.into_iter()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", usage());
+            process::exit(2);
+        }
+    };
+
+    let listener = match TcpListener::bind(&config.listen) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Impossible d'écouter sur {} : {}", config.listen, err);
+            process::exit(1);
+        }
+    };
+    println!("Proxy en écoute sur {} -> {}", config.listen, config.server);
+    for rule in &config.rules {
+        println!("Règle : {:?}", rule);
+    }
+
+    let rules = Arc::new(config.rules);
+    let connections = AtomicUsize::new(0);
+    for client in listener.incoming() {
+        let client = match client {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("Connexion entrante refusée : {}", err);
+                continue;
+            }
+        };
+        let id = connections.fetch_add(1, Ordering::Relaxed) + 1;
+        let server = match TcpStream::connect(&config.server) {
+            Ok(server) => server,
+            Err(err) => {
+                eprintln!("[#{}] Connexion au serveur {} impossible : {}", id, config.server, err);
+                continue;
+            }
+        };
+        println!("[#{}] Nouvelle connexion depuis {:?}", id, client.peer_addr().ok());
+        if let Err(err) = start_session(id, client, server, Arc::clone(&rules)) {
+            eprintln!("[#{}] Erreur de démarrage de la session : {}", id, err);
+        }
+    }
+}
+
+// Lancer un thread par sens de circulation
+fn start_session(id: usize, client: TcpStream, server: TcpStream, rules: Arc<Vec<Rule>>) -> std::io::Result<()> {
+    let (client_reader, server_writer) = (client.try_clone()?, server.try_clone()?);
+    let outbound_rules = Arc::clone(&rules);
+    thread::spawn(move || forward(id, FrameDirection::Outbound, client_reader, server_writer, &outbound_rules));
+    thread::spawn(move || forward(id, FrameDirection::Inbound, server, client, &rules));
+    Ok(())
+}
+
+fn forward(id: usize, direction: FrameDirection, mut from: TcpStream, mut to: TcpStream, rules: &[Rule]) {
+    let codec = FrameCodec::default();
+    loop {
+        let frame = match codec.recv_frame(&mut from) {
+            Ok(frame) => frame,
+            Err(err) => {
+                if is_connection_closed(&err) {
+                    println!("[#{}] {:?} : connexion fermée", id, direction);
+                } else {
+                    eprintln!("[#{}] {:?} : erreur de lecture : {}", id, direction, err);
+                }
+                break;
+            }
+        };
+        let mut text = String::from_utf8_lossy(&frame).into_owned();
+        print_frame(id, direction, &text);
+
+        let mut dropped = false;
+        for rule in rules {
+            if !rule.matches(direction, &text) {
+                continue;
+            }
+            match &rule.action {
+                RuleAction::Delay(delay) => {
+                    println!("[#{}] règle : trame retardée de {:?}", id, delay);
+                    thread::sleep(*delay);
+                }
+                RuleAction::Drop => {
+                    println!("[#{}] règle : trame supprimée", id);
+                    dropped = true;
+                }
+                RuleAction::Replace { from, to } => {
+                    text = text.replace(from.as_str(), to);
+                    println!("[#{}] règle : trame modifiée en {}", id, text);
+                }
+            }
+        }
+        if dropped {
+            continue;
+        }
+        if let Err(err) = codec.send_frame(&mut to, text.as_bytes()) {
+            eprintln!("[#{}] {:?} : erreur d'écriture : {}", id, direction, err);
+            break;
+        }
+    }
+    // Fermer les deux sockets pour arrêter aussi le thread de l'autre sens
+    let _ = from.shutdown(Shutdown::Both);
+    let _ = to.shutdown(Shutdown::Both);
+}
+
+fn print_frame(id: usize, direction: FrameDirection, text: &str) {
+    let arrow = match direction {
+        FrameDirection::Outbound => "client -> serveur",
+        FrameDirection::Inbound => "serveur -> client",
+    };
+    println!("[#{}] {} : {}", id, arrow, text);
+    match direction {
+        FrameDirection::Outbound => match serde_json::from_str::<ClientMessage>(text) {
+            Ok(message) => println!("{:#?}", message),
+            Err(err) => println!("  (message client non reconnu : {})", err),
+        },
+        FrameDirection::Inbound => match serde_json::from_str::<ServerMessage>(text) {
+            Ok(ServerMessage::RadarView(view)) => {
+                println!("RadarView({})", view);
+                match decoder(&view) {
+                    Ok(radar_view) => {
+                        for line in radar_view.to_string().lines() {
+                            println!("  {}", line);
+                        }
+                    }
+                    Err(err) => println!("  (radar illisible : {})", err),
+                }
+            }
+            Ok(message) => println!("{:#?}", message),
+            Err(err) => println!("  (message serveur non reconnu : {})", err),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn parses_valid_rules() {
+        assert_eq!(
+            Rule::parse("in:RadarView:delay=500"),
+            Ok(Rule {
+                direction: Some(FrameDirection::Inbound),
+                pattern: "RadarView".to_string(),
+                action: RuleAction::Delay(Duration::from_millis(500)),
+            })
+        );
+        assert_eq!(
+            Rule::parse("out:MoveTo:drop"),
+            Ok(Rule { direction: Some(FrameDirection::Outbound), pattern: "MoveTo".to_string(), action: RuleAction::Drop })
+        );
+        // L'action peut contenir des deux-points, pas le motif qui s'arrête au premier
+        assert_eq!(
+            Rule::parse("any:*:replace={\"MoveTo\":\"Front\"}=>{\"MoveTo\":\"Back\"}").map(|rule| (rule.direction, rule.action)),
+            Ok((
+                None,
+                RuleAction::Replace { from: "{\"MoveTo\":\"Front\"}".to_string(), to: "{\"MoveTo\":\"Back\"}".to_string() }
+            ))
+        );
+        assert_eq!(Rule::parse("in:{\"Hint\":drop").map(|rule| rule.pattern), Ok("{\"Hint\"".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rule in [
+            "",
+            "in:RadarView",
+            "both:RadarView:drop",
+            "in:RadarView:delay=soon",
+            "in:RadarView:delay=-1",
+            "in:RadarView:replace=Front",
+            "in:RadarView:explode",
+        ] {
+            assert!(Rule::parse(rule).is_err(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn rules_match_direction_and_pattern() {
+        let radar = Rule::parse("in:RadarView:drop").unwrap();
+        assert!(radar.matches(FrameDirection::Inbound, "{\"RadarView\":\"ieysGjGO8papd/a\"}"));
+        assert!(!radar.matches(FrameDirection::Outbound, "{\"RadarView\":\"ieysGjGO8papd/a\"}"));
+        assert!(!radar.matches(FrameDirection::Inbound, "{\"Hint\":\"SOSHelper\"}"));
+
+        let everything = Rule::parse("any:*:drop").unwrap();
+        assert!(everything.matches(FrameDirection::Inbound, "{}"));
+        assert!(everything.matches(FrameDirection::Outbound, "{}"));
+    }
+
+    #[test]
+    fn parses_arguments() {
+        let config = parse_args(args(&[])).unwrap();
+        assert_eq!((config.listen.as_str(), config.server.as_str()), (DEFAULT_LISTEN, DEFAULT_SERVER));
+        assert!(config.rules.is_empty());
+
+        let config = parse_args(args(&[
+            "--server", "example.org:8778", "--rule", "out:MoveTo:drop", "--listen", "0.0.0.0:9000", "--rule", "in:*:delay=10",
+        ]))
+        .unwrap();
+        assert_eq!(config.listen, "0.0.0.0:9000");
+        assert_eq!(config.server, "example.org:8778");
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[1].action, RuleAction::Delay(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(parse_args(args(&["--listen"])).err(), Some("Valeur manquante pour --listen".to_string()));
+        assert_eq!(parse_args(args(&["--verbose"])).err(), Some("Argument inattendu : --verbose".to_string()));
+        assert!(parse_args(args(&["--rule", "in:RadarView"])).is_err());
+    }
+}
@@ -1,11 +1,325 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Write};
-use std::net::TcpStream;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn set_tcp_stream()-> io::Result<TcpStream>{
-    
-    TcpStream::connect("localhost:8778") 
-       
-    
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::config_module::ServerConfig;
+
+// Taille maximale par défaut d'une trame (1 Mo), largement au-dessus des messages du jeu
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+// Se connecter au serveur configuré, en réessayant avec une attente exponentielle
+pub fn set_tcp_stream(config: &ServerConfig)-> io::Result<TcpStream>{
+    let attempts = config.retry.max_attempts.max(1);
+    let mut last_error = None;
+
+    for attempt in 0..attempts {
+        match connect_once(config) {
+            Ok(stream) => {
+                stream.set_read_timeout(config.read_timeout)?;
+                stream.set_write_timeout(config.write_timeout)?;
+                return Ok(stream);
+            }
+            Err(err) => {
+                eprintln!(
+                    "Connexion à {} impossible (tentative {}/{}) : {}",
+                    config.address(), attempt + 1, attempts, err
+                );
+                last_error = Some(err);
+            }
+        }
+        if attempt + 1 < attempts {
+            thread::sleep(config.retry.delay_for(attempt));
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| io::Error::other("aucune tentative de connexion")))
+}
+
+// Une tentative de connexion sur chacune des adresses résolues, avec délai maximal
+fn connect_once(config: &ServerConfig) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for address in (config.host.as_str(), config.port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, config.connect_timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("adresse introuvable : {}", config.address()))
+    }))
+}
+
+// Marqueur d'une fermeture propre de la connexion : le flux se termine entre deux trames
+#[derive(Debug)]
+pub struct ConnectionClosed;
+
+impl fmt::Display for ConnectionClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "connexion fermée par le serveur")
+    }
+}
+
+impl Error for ConnectionClosed {}
+
+pub fn is_connection_closed(err: &io::Error) -> bool {
+    err.get_ref().is_some_and(|inner| inner.is::<ConnectionClosed>())
+}
+
+pub fn is_timeout(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+fn truncated_frame(received: usize, expected: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!("Trame tronquée : {} octets reçus sur {}", received, expected),
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameDirection {
+    Inbound,    // serveur -> client
+    Outbound,   // client -> serveur
+}
+
+// Une ligne du fichier d'enregistrement (format JSON Lines)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    pub direction: FrameDirection,
+    pub player: String,
+    pub timestamp_ms: u64,
+    pub frame: String,  // JSON brut, tel qu'il a circulé sur la socket
+}
+
+// Nature de la connexion enregistrée, qui distingue les fichiers d'une équipe et d'un joueur de même nom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionKind {
+    Team,
+    Player,
+}
+
+impl SessionKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            SessionKind::Team => "team",
+            SessionKind::Player => "player",
+        }
+    }
 }
 
+// Enregistreur de session : écrit chaque trame dans <dossier>/<nature>-<nom>-<horodatage>.jsonl,
+// un fichier par partie pour que le rejeu ne mélange pas deux parties
+#[derive(Debug, Clone)]
+pub struct SessionRecorder {
+    player: String,
+    path: PathBuf,
+    file: Arc<Mutex<File>>,
+}
+
+impl SessionRecorder {
+    pub fn create(directory: &Path, kind: SessionKind, player: &str) -> io::Result<Self> {
+        fs::create_dir_all(directory)?;
+        // Le fichier reste ouvert pour toute la partie, reconnexions comprises, et n'est jamais celui
+        // d'une autre partie : si l'horodatage est déjà pris, la milliseconde suivante est essayée
+        let mut timestamp = timestamp_ms();
+        let (path, file) = loop {
+            let path = directory.join(format!("{}-{}-{}.jsonl", kind.prefix(), sanitize_file_name(player), timestamp));
+            match OpenOptions::new().create_new(true).append(true).open(&path) {
+                Ok(file) => break (path, file),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => timestamp += 1,
+                Err(err) => return Err(err),
+            }
+        };
+        Ok(SessionRecorder {
+            player: player.to_string(),
+            path,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, direction: FrameDirection, payload: &[u8]) -> io::Result<()> {
+        let entry = RecordedFrame {
+            direction,
+            player: self.player.clone(),
+            timestamp_ms: timestamp_ms(),
+            frame: String::from_utf8_lossy(payload).into_owned(),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
 
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| io::Error::other("fichier d'enregistrement verrouillé"))?;
+        file.write_all(&line)?;
+        file.flush()
+    }
+}
+
+// Lire toutes les trames d'un fichier d'enregistrement
+pub fn read_recording(path: &Path) -> io::Result<Vec<RecordedFrame>> {
+    let content = fs::read_to_string(path)?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(io::Error::from))
+        .collect()
+}
+
+fn timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Nom de fichier sans ambiguïté : les autres caractères, '_' compris, deviennent '_' suivi
+// de leurs octets UTF-8 en hexadécimal, si bien que deux noms différents donnent deux fichiers différents
+fn sanitize_file_name(name: &str) -> String {
+    let mut sanitized = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            sanitized.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                sanitized.push_str(&format!("_{:02x}", byte));
+            }
+        }
+    }
+    sanitized
+}
+
+// Codec des trames du protocole : longueur u32 little-endian suivie du corps JSON
+#[derive(Debug, Clone)]
+pub struct FrameCodec {
+    max_frame_size: usize,
+    recorder: Option<SessionRecorder>,
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        FrameCodec::new(DEFAULT_MAX_FRAME_SIZE)
+    }
+}
+
+impl FrameCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        FrameCodec { max_frame_size, recorder: None }
+    }
+
+    // Codec d'une session : enregistre les trames si un dossier d'enregistrement est configuré
+    pub fn for_session(record_directory: Option<&Path>, kind: SessionKind, name: &str) -> Self {
+        let codec = FrameCodec::default();
+        let Some(directory) = record_directory else {
+            return codec;
+        };
+        match SessionRecorder::create(directory, kind, name) {
+            Ok(recorder) => {
+                println!("Enregistrement de la session de {} dans {}", name, recorder.path().display());
+                codec.with_recorder(recorder)
+            }
+            Err(err) => {
+                eprintln!("Impossible d'enregistrer la session de {} : {}", name, err);
+                codec
+            }
+        }
+    }
+
+    pub fn with_recorder(mut self, recorder: SessionRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    fn record(&self, direction: FrameDirection, payload: &[u8]) {
+        if let Some(recorder) = &self.recorder {
+            if let Err(err) = recorder.record(direction, payload) {
+                eprintln!("Erreur d'enregistrement de trame : {}", err);
+            }
+        }
+    }
+
+    // Envoyer une trame brute (longueur + contenu)
+    pub fn send_frame<W: Write>(&self, stream: &mut W, payload: &[u8]) -> io::Result<()> {
+        if payload.len() > self.max_frame_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Trame trop grande : {} octets (max {})", payload.len(), self.max_frame_size),
+            ));
+        }
+        self.record(FrameDirection::Outbound, payload);
+        let length = payload.len() as u32;
+        stream.write_all(&length.to_le_bytes())?;
+        stream.write_all(payload)?;
+        stream.flush()
+    }
+
+    // Lire une trame brute, en refusant les longueurs supérieures au maximum configuré.
+    // Une fin de flux avant le premier octet est une fermeture propre (ConnectionClosed),
+    // une fin de flux au milieu d'une trame est une trame tronquée.
+    pub fn recv_frame<R: Read>(&self, stream: &mut R) -> io::Result<Vec<u8>> {
+        let mut length_buffer = [0u8; 4];
+        let mut filled = 0;
+        while filled < length_buffer.len() {
+            match stream.read(&mut length_buffer[filled..]) {
+                Ok(0) if filled == 0 => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, ConnectionClosed))
+                }
+                Ok(0) => return Err(truncated_frame(filled, length_buffer.len())),
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let length = u32::from_le_bytes(length_buffer) as usize;
+        if length > self.max_frame_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Trame annoncée trop grande : {} octets (max {})", length, self.max_frame_size),
+            ));
+        }
+
+        let mut payload = vec![0u8; length];
+        let mut filled = 0;
+        while filled < length {
+            match stream.read(&mut payload[filled..]) {
+                Ok(0) => return Err(truncated_frame(filled, length)),
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.record(FrameDirection::Inbound, &payload);
+        Ok(payload)
+    }
+
+    // Sérialiser un message en JSON et l'envoyer dans une trame
+    pub fn send_message<W: Write, T: Serialize>(&self, stream: &mut W, message: &T) -> io::Result<()> {
+        let serialized = serde_json::to_vec(message)?;
+        self.send_frame(stream, &serialized)
+    }
+
+    // Lire une trame et la désérialiser en message typé
+    pub fn recv_message<R: Read, T: DeserializeOwned>(&self, stream: &mut R) -> io::Result<T> {
+        let payload = self.recv_frame(stream)?;
+        serde_json::from_slice(&payload).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Message invalide ({}) : {}", err, String::from_utf8_lossy(&payload)),
+            )
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::direction_module::Heading;
+use crate::map_module::Position;
+
+// Erreur minimale supposée entre une droite mesurée et la sortie, en cellules
+const MIN_LINE_ERROR: f64 = 0.5;
+
+// Indication de boussole reçue par un joueur : l'angle est relatif à son orientation,
+// en degrés dans le sens horaire (0 devant, 90 à droite)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompassReading {
+    pub position: Position,
+    pub heading: Heading,
+    pub angle: f32,
+}
+
+impl CompassReading {
+    // Cap absolu vers la sortie, en degrés dans le sens horaire depuis le nord, entre 0 et 360
+    pub fn bearing(&self) -> f32 {
+        (self.heading.facing().degrees() + self.angle).rem_euclid(360.0)
+    }
+
+    // Vecteur unitaire vers la sortie dans le repère de la carte (y croît vers le sud)
+    fn unit_vector(&self) -> (f64, f64) {
+        let radians = (self.bearing() as f64).to_radians();
+        (radians.sin(), -radians.cos())
+    }
+
+    fn origin(&self) -> (f64, f64) {
+        (self.position.0 as f64, self.position.1 as f64)
+    }
+
+    // Distance entre un point et la demi-droite partant du joueur vers la sortie
+    fn distance_to(&self, (x, y): (f64, f64)) -> f64 {
+        let (ox, oy) = self.origin();
+        let (ux, uy) = self.unit_vector();
+        let (vx, vy) = (x - ox, y - oy);
+        let along = vx * ux + vy * uy;
+        if along < 0.0 {
+            // Point derrière le joueur : la sortie ne peut pas y être
+            (vx * vx + vy * vy).sqrt()
+        } else {
+            (vx * uy - vy * ux).abs()
+        }
+    }
+}
+
+// Position estimée de la sortie dans le repère de la carte
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExitEstimate {
+    pub x: f64,
+    pub y: f64,
+    // Écart-type de l'estimation, en cellules : grand si les caps sont presque parallèles
+    pub uncertainty: f64,
+    pub readings: usize,
+}
+
+impl ExitEstimate {
+    pub fn cell(&self) -> Position {
+        (self.x.round() as i32, self.y.round() as i32)
+    }
+}
+
+impl fmt::Display for ExitEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sortie vers ({:.1}, {:.1}) à ±{:.1} cellules d'après {} mesures",
+            self.x, self.y, self.uncertainty, self.readings
+        )
+    }
+}
+
+// Intersecter les caps mesurés depuis des positions différentes : point le plus proche de toutes
+// les droites au sens des moindres carrés. None s'il n'y a pas deux caps non parallèles.
+pub fn estimate_exit(readings: &[CompassReading]) -> Option<ExitEstimate> {
+    if readings.len() < 2 {
+        return None;
+    }
+    // Somme des projecteurs orthogonaux (I - u uᵀ) et du second membre (I - u uᵀ) p
+    let (mut a, mut b, mut c, mut rx, mut ry) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for reading in readings {
+        let (ux, uy) = reading.unit_vector();
+        let (px, py) = reading.origin();
+        let (pa, pb, pc) = (1.0 - ux * ux, -ux * uy, 1.0 - uy * uy);
+        a += pa;
+        b += pb;
+        c += pc;
+        rx += pa * px + pb * py;
+        ry += pb * px + pc * py;
+    }
+    let determinant = a * c - b * b;
+    // Toutes les droites parallèles : pas d'intersection
+    if determinant.abs() < 1e-6 * (readings.len() * readings.len()) as f64 {
+        return None;
+    }
+    let (x, y) = ((c * rx - b * ry) / determinant, (a * ry - b * rx) / determinant);
+    // Erreur sur chaque droite : l'écart observé, mais au moins une demi-cellule puisque les
+    // positions sont des cellules entières ; amplifiée par la géométrie via la trace de l'inverse
+    let squares: f64 = readings.iter().map(|reading| reading.distance_to((x, y)).powi(2)).sum();
+    let line_error = (squares / readings.len() as f64).sqrt().max(MIN_LINE_ERROR);
+    Some(ExitEstimate {
+        x,
+        y,
+        uncertainty: line_error * ((a + c) / determinant).sqrt(),
+        readings: readings.len(),
+    })
+}
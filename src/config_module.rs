@@ -0,0 +1,227 @@
+use std::{env, fs, path::{Path, PathBuf}, time::Duration};
+
+// Valeurs par défaut du client
+pub const DEFAULT_HOST: &str = "localhost";
+pub const DEFAULT_PORT: u16 = 8778;
+pub const DEFAULT_CONFIG_FILE: &str = "sauve_qui_peut.conf";
+
+// Variables d'environnement reconnues
+pub const SERVER_ENV_VAR: &str = "SQP_SERVER";
+pub const CONFIG_ENV_VAR: &str = "SQP_CONFIG";
+
+// Politique de reconnexion : attente exponentielle entre deux tentatives
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Délai à attendre après l'échec de la tentative `attempt` (0 = première tentative)
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub connect_timeout: Duration,
+    // None : pas de délai maximal sur la socket
+    pub read_timeout: Option<Duration>,
+    pub write_timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+    // Reconnexions d'un joueur dont la connexion est perdue en cours de partie,
+    // max_attempts compte les tentatives successives depuis la dernière session qui a reçu un radar
+    pub reconnect: RetryPolicy,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            host: DEFAULT_HOST.to_string(),
+            port: DEFAULT_PORT,
+            connect_timeout: Duration::from_secs(3),
+            read_timeout: Some(Duration::from_secs(30)),
+            write_timeout: Some(Duration::from_secs(5)),
+            retry: RetryPolicy::default(),
+            reconnect: RetryPolicy {
+                max_attempts: 3,
+                initial_delay: Duration::from_millis(500),
+                max_delay: Duration::from_secs(10),
+            },
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    // Appliquer une adresse de la forme "hôte:port" ou "hôte"
+    fn set_address(&mut self, address: &str) -> Result<(), String> {
+        match address.rsplit_once(':') {
+            Some((host, port)) => {
+                self.host = host.to_string();
+                self.port = parse_value("port", port)?;
+            }
+            None => self.host = address.to_string(),
+        }
+        if self.host.is_empty() {
+            return Err(format!("Adresse de serveur invalide : {}", address));
+        }
+        Ok(())
+    }
+
+    // Appliquer une option, qu'elle vienne de la ligne de commande ou du fichier de configuration
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "server" => self.set_address(value)?,
+            "host" => self.host = value.to_string(),
+            "port" => self.port = parse_value(key, value)?,
+            "connect_timeout_ms" => self.connect_timeout = parse_connect_timeout(key, value)?,
+            "read_timeout_ms" => self.read_timeout = parse_timeout(key, value)?,
+            "write_timeout_ms" => self.write_timeout = parse_timeout(key, value)?,
+            "retry_attempts" => self.retry.max_attempts = parse_value(key, value)?,
+            "retry_initial_delay_ms" => self.retry.initial_delay = Duration::from_millis(parse_value(key, value)?),
+            "retry_max_delay_ms" => self.retry.max_delay = Duration::from_millis(parse_value(key, value)?),
+            "reconnect_attempts" => self.reconnect.max_attempts = parse_value(key, value)?,
+            "reconnect_initial_delay_ms" => self.reconnect.initial_delay = Duration::from_millis(parse_value(key, value)?),
+            "reconnect_max_delay_ms" => self.reconnect.max_delay = Duration::from_millis(parse_value(key, value)?),
+            _ => return Err(format!("Option inconnue : {}", key)),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub server: ServerConfig,
+    // Dossier où enregistrer les trames de chaque session (désactivé si None)
+    pub record_dir: Option<PathBuf>,
+    // Fichier d'enregistrement à rejouer hors ligne au lieu de jouer une partie
+    pub replay: Option<PathBuf>,
+}
+
+impl Config {
+    // Charger la configuration. Priorité croissante :
+    // valeurs par défaut < fichier de configuration < variable d'environnement < ligne de commande
+    pub fn load<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let options = parse_args(args)?;
+        let mut config = Config::default();
+
+        let config_file = options
+            .iter()
+            .find(|(key, _)| key == "config")
+            .map(|(_, value)| value.clone())
+            .or_else(|| env::var(CONFIG_ENV_VAR).ok());
+        match config_file {
+            Some(path) => config.apply_file(Path::new(&path))?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                config.apply_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => {}
+        }
+
+        if let Ok(address) = env::var(SERVER_ENV_VAR) {
+            config.server.set_address(&address)?;
+        }
+
+        for (key, value) in options.iter().filter(|(key, _)| key != "config") {
+            config.set(key, value)?;
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "record_dir" => self.record_dir = Some(PathBuf::from(value)),
+            "replay" => self.replay = Some(PathBuf::from(value)),
+            _ => self.server.set(key, value)?,
+        }
+        Ok(())
+    }
+
+    // Fichier texte de lignes "clé = valeur", les lignes commençant par '#' sont ignorées
+    fn apply_file(&mut self, path: &Path) -> Result<(), String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("Impossible de lire {} : {}", path.display(), err))?;
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("{}:{} : ligne invalide '{}'", path.display(), number + 1, line))?;
+            self.set(key.trim(), value.trim())
+                .map_err(|err| format!("{}:{} : {}", path.display(), number + 1, err))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn usage() -> &'static str {
+    "Usage : sauve_qui_peut [--server hôte:port] [--host hôte] [--port port] [--config fichier]\n\
+     \x20                     [--connect-timeout-ms ms] [--read-timeout-ms ms] [--write-timeout-ms ms]\n\
+     \x20                     [--retry-attempts n] [--retry-initial-delay-ms ms] [--retry-max-delay-ms ms]\n\
+     \x20                     [--reconnect-attempts n] [--reconnect-initial-delay-ms ms]\n\
+     \x20                     [--reconnect-max-delay-ms ms] [--record-dir dossier]\n\
+     \x20                     [--replay fichier.jsonl]"
+}
+
+// Transformer "--clé valeur" (ou "--clé=valeur") en paires (clé, valeur), les tirets devenant des '_'
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Vec<(String, String)>, String> {
+    let mut options = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let option = arg
+            .strip_prefix("--")
+            .ok_or_else(|| format!("Argument inattendu : {}", arg))?;
+        let (key, value) = match option.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Valeur manquante pour --{}", option))?;
+                (option.to_string(), value)
+            }
+        };
+        options.push((key.replace('-', "_"), value));
+    }
+    Ok(options)
+}
+
+// Un délai de 0 ms désactive le délai maximal
+fn parse_timeout(key: &str, value: &str) -> Result<Option<Duration>, String> {
+    let millis: u64 = parse_value(key, value)?;
+    Ok((millis > 0).then(|| Duration::from_millis(millis)))
+}
+
+// Contrairement aux délais de lecture et d'écriture, la connexion ne peut pas se passer de délai maximal
+fn parse_connect_timeout(key: &str, value: &str) -> Result<Duration, String> {
+    match parse_value(key, value)? {
+        0 => Err(format!("Valeur invalide pour {} : le délai de connexion doit être d'au moins 1 ms", key)),
+        millis => Ok(Duration::from_millis(millis)),
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Valeur invalide pour {} : {}", key, value))
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+
+use crate::protocol_module::RelativeDirection;
+use crate::radar_module::Offset;
+
+// Direction absolue dans le labyrinthe, le nord en haut :
+// x croît vers l'est et y vers le sud, comme dans Maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    // Nombre de quarts de tour dans le sens horaire depuis le nord
+    fn quarter_turns(self) -> u8 {
+        match self {
+            Direction::North => 0,
+            Direction::East  => 1,
+            Direction::South => 2,
+            Direction::West  => 3,
+        }
+    }
+
+    fn from_quarter_turns(turns: u8) -> Self {
+        Direction::ALL[(turns % 4) as usize]
+    }
+
+    // Cap en degrés dans le sens horaire depuis le nord, comme les angles de RelativeCompass
+    pub fn degrees(self) -> f32 {
+        self.quarter_turns() as f32 * 90.0
+    }
+
+    pub fn opposite(self) -> Self {
+        Direction::from_quarter_turns(self.quarter_turns() + 2)
+    }
+
+    // Déplacement d'une cellule dans cette direction
+    pub fn offset(self) -> Offset {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East  => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West  => (-1, 0),
+        }
+    }
+
+    // Direction absolue d'un mouvement relatif, pour un joueur tourné vers `self`
+    pub fn turn(self, relative: RelativeDirection) -> Self {
+        Direction::from_quarter_turns(self.quarter_turns() + relative_quarter_turns(relative))
+    }
+
+    // Mouvement relatif à faire, pour un joueur tourné vers `facing`, pour aller vers `self`
+    pub fn relative_to(self, facing: Direction) -> RelativeDirection {
+        match (4 + self.quarter_turns() - facing.quarter_turns()) % 4 {
+            0 => RelativeDirection::Front,
+            1 => RelativeDirection::Right,
+            2 => RelativeDirection::Back,
+            _ => RelativeDirection::Left,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::North => write!(f, "Nord"),
+            Direction::East  => write!(f, "Est"),
+            Direction::South => write!(f, "Sud"),
+            Direction::West  => write!(f, "Ouest"),
+        }
+    }
+}
+
+fn relative_quarter_turns(relative: RelativeDirection) -> u8 {
+    match relative {
+        RelativeDirection::Front => 0,
+        RelativeDirection::Right => 1,
+        RelativeDirection::Back  => 2,
+        RelativeDirection::Left  => 3,
+    }
+}
+
+// Angle d'un mouvement relatif, en degrés dans le sens horaire depuis l'avant
+pub fn relative_degrees(relative: RelativeDirection) -> f32 {
+    relative_quarter_turns(relative) as f32 * 90.0
+}
+
+// Écart entre deux angles en degrés, entre 0 et 180
+pub fn angular_distance(first: f32, second: f32) -> f32 {
+    let difference = (first - second).rem_euclid(360.0);
+    difference.min(360.0 - difference)
+}
+
+// Tourner un mouvement relatif de `turns` quarts de tour dans le sens horaire
+pub fn rotate_relative(relative: RelativeDirection, turns: u8) -> RelativeDirection {
+    Direction::from_quarter_turns(relative_quarter_turns(relative) + turns).relative_to(Direction::North)
+}
+
+// Tourner une position relative de `turns` quarts de tour dans le sens horaire
+pub fn rotate_offset((dx, dy): Offset, turns: u8) -> Offset {
+    match turns % 4 {
+        0 => (dx, dy),
+        1 => (-dy, dx),
+        2 => (-dx, -dy),
+        _ => (dy, -dx),
+    }
+}
+
+// Orientation du joueur. Le radar est toujours vu dans le sens du dernier déplacement :
+// après un mouvement, le joueur regarde dans la direction où il vient d'aller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heading {
+    facing: Direction,
+}
+
+impl Default for Heading {
+    // L'orientation réelle au départ est inconnue, le nord sert de repère
+    fn default() -> Self {
+        Heading::new(Direction::North)
+    }
+}
+
+impl Heading {
+    pub fn new(facing: Direction) -> Self {
+        Heading { facing }
+    }
+
+    pub fn facing(&self) -> Direction {
+        self.facing
+    }
+
+    pub fn to_absolute(&self, relative: RelativeDirection) -> Direction {
+        self.facing.turn(relative)
+    }
+
+    pub fn to_relative(&self, absolute: Direction) -> RelativeDirection {
+        absolute.relative_to(self.facing)
+    }
+
+    // Mettre à jour l'orientation après un déplacement réussi, et renvoyer la direction absolue suivie
+    pub fn record_move(&mut self, relative: RelativeDirection) -> Direction {
+        self.facing = self.to_absolute(relative);
+        self.facing
+    }
+
+    // Position absolue d'une cellule vue à `offset` dans le radar
+    pub fn offset_to_absolute(&self, offset: Offset) -> Offset {
+        rotate_offset(offset, self.facing.quarter_turns())
+    }
+
+    pub fn offset_to_relative(&self, offset: Offset) -> Offset {
+        rotate_offset(offset, 4 - self.facing.quarter_turns())
+    }
+
+    // Quarts de tour horaires pour passer du repère du joueur au repère absolu
+    pub fn quarter_turns(&self) -> u8 {
+        self.facing.quarter_turns()
+    }
+}
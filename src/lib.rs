@@ -0,0 +1,15 @@
+pub mod team_module;
+
+pub mod player_module;
+pub mod communication_module;
+pub mod protocol_module;
+pub mod config_module;
+pub mod replay_module;
+pub mod radar_module;
+pub mod direction_module;
+pub mod validation_module;
+pub mod map_module;
+pub mod compass_module;
+pub mod team_map_module;
+pub mod base64_module;
+pub mod maze_module;
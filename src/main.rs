@@ -1,16 +1,41 @@
-use std::{io::{self, Write}, thread};
-use team_module::*;
-use player_module::*;
-use communication_module::*;
-mod team_module;
-
-mod player_module;
-mod communication_module;
+use std::{env, io::{self, Write}, process, thread};
+use sauve_qui_peut::config_module::{usage, Config};
+use sauve_qui_peut::replay_module;
+use sauve_qui_peut::team_module::*;
+use sauve_qui_peut::player_module::*;
 
 
 fn main() {
-    
-    
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", usage());
+        return;
+    }
+    let config = match Config::load(args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Erreur de configuration : {}", err);
+            eprintln!("{}", usage());
+            process::exit(2);
+        }
+    };
+    if let Some(path) = &config.replay {
+        match replay_module::replay_file(path) {
+            Ok(report) => {
+                replay_module::print_report(&report);
+                if report.divergences().next().is_some() {
+                    process::exit(1);
+                }
+            }
+            Err(err) => {
+                eprintln!("Erreur lors du rejeu de {} : {}", path.display(), err);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    println!("Serveur : {}", config.server.address());
+
     loop {
         display_menu();
 
@@ -24,13 +49,16 @@ fn main() {
         match choice.trim() {
             "1" => {
                 println!("Enregistrement des équipes...");
+                let config = config.clone();
                 let creation=thread::spawn(move || {
-                    if let Err(err) = register_team_and_players() {
+                    if let Err(err) = register_team_and_players(&config) {
                         eprintln!("Erreur lors de l'enregistrement des équipes : {}", err);
                        
                     }
                 });
-                creation.join();
+                if creation.join().is_err() {
+                    eprintln!("Le thread d'enregistrement s'est arrêté brutalement");
+                }
                 break;
             }
             "2" => {
@@ -0,0 +1,392 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::direction_module::{Direction, Heading};
+use crate::radar_module::{neighbour, Cell, Passage, RadarCell, RadarView};
+
+// Position d'une cellule sur la carte : x croît vers l'est et y vers le sud, le nord étant devant le joueur
+// au début de la partie. L'origine est la position de départ du joueur.
+pub type Position = (i32, i32);
+
+// Plus grand côté de grille accepté dans Hint::GridSize, bien au-delà des labyrinthes du jeu
+pub const MAX_GRID_SIZE: u32 = 10_000;
+
+// Alignement d'une carte sur une autre : nombre de décalages essayés au plus,
+// et passages connus des deux côtés nécessaires pour retenir un décalage
+const MAX_ALIGNMENT_CANDIDATES: i64 = 10_000;
+const MIN_ALIGNMENT_WALLS: usize = 6;
+
+pub fn step((x, y): Position, direction: Direction) -> Position {
+    let (dx, dy) = direction.offset();
+    (x + dx, y + dy)
+}
+
+// Carte du labyrinthe construite au fil des radars reçus par un joueur
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorldMap {
+    // Dernier contenu vu pour chaque cellule
+    cells: HashMap<Position, RadarCell>,
+    // Chaque mur est rangé une seule fois, sur le côté nord ou ouest d'une cellule
+    walls: HashMap<(Position, Direction), Passage>,
+    // Taille du labyrinthe (colonnes, lignes) donnée par Hint::GridSize
+    grid_size: Option<(u32, u32)>,
+    // Bords possibles de la grille : côté et dernière colonne (ouest, est) ou ligne (nord, sud) dans la grille.
+    // Le radar cache aussi les cellules de la grille derrière un mur : un candidat n'est retenu
+    // que tant qu'il ne contredit rien de ce qui est connu.
+    borders: HashSet<(Direction, i32)>,
+}
+
+// Décalages possibles, par axe de la carte, entre la carte et le coin de la grille situé au nord-ouest
+// de la carte : position depuis ce coin = position sur la carte + décalage.
+// L'orientation du joueur au départ n'est pas connue : ce coin n'est la cellule (0, 0) du serveur
+// que si le joueur est parti tourné vers le nord de la grille.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridOffsets {
+    pub x: (i32, i32),
+    pub y: (i32, i32),
+}
+
+impl GridOffsets {
+    // Décalage est-ouest, une fois qu'une seule valeur reste possible
+    pub fn fixed_x(&self) -> Option<i32> {
+        (self.x.0 == self.x.1).then_some(self.x.0)
+    }
+
+    // Décalage nord-sud, une fois qu'une seule valeur reste possible
+    pub fn fixed_y(&self) -> Option<i32> {
+        (self.y.0 == self.y.1).then_some(self.y.0)
+    }
+}
+
+impl WorldMap {
+    pub fn new() -> Self {
+        WorldMap::default()
+    }
+
+    // Ajouter un radar reçu par un joueur en `position`, orienté selon `heading`.
+    // Ce qui est hors de vue ne remplace jamais ce qui est déjà connu.
+    pub fn merge(&mut self, radar: &RadarView, position: Position, heading: Heading) {
+        let radar = radar.to_absolute(heading);
+        let (x, y) = position;
+        for ((dx, dy), cell) in radar.visible_cells() {
+            if !matches!(cell, RadarCell::Invalid(_)) {
+                self.cells.insert((x + dx, y + dy), cell);
+            }
+        }
+        for wall in radar.visible_walls() {
+            if matches!(wall.passage, Passage::Open | Passage::Wall) {
+                let (dx, dy) = wall.from;
+                // Dans le radar absolu, Front est le nord et Right l'est
+                let direction = Direction::North.turn(wall.direction);
+                self.walls.insert(wall_key((x + dx, y + dy), direction), wall.passage);
+
+                // Un mur entre une cellule visible et une cellule indéfinie du radar est peut-être un bord de la grille
+                let far = neighbour(wall.from, wall.direction);
+                if wall.passage == Passage::Wall && far.0.abs() <= 1 && far.1.abs() <= 1 {
+                    let border = match (radar.cell(dx, dy), radar.cell(far.0, far.1)) {
+                        (RadarCell::Undefined, RadarCell::Undefined) => None,
+                        (_, RadarCell::Undefined) => Some(((x + dx, y + dy), direction)),
+                        (RadarCell::Undefined, _) => Some(((x + far.0, y + far.1), direction.opposite())),
+                        _ => None,
+                    };
+                    if let Some(((inside_x, inside_y), side)) = border {
+                        let edge = match side {
+                            Direction::West | Direction::East => inside_x,
+                            Direction::North | Direction::South => inside_y,
+                        };
+                        self.borders.insert((side, edge));
+                    }
+                }
+            }
+        }
+    }
+
+    // Noter un passage appris autrement que par le radar (mur heurté par exemple)
+    pub fn record_passage(&mut self, position: Position, direction: Direction, passage: Passage) {
+        self.walls.insert(wall_key(position, direction), passage);
+    }
+
+    // Ajouter les connaissances d'une autre carte, décalée de `shift`, sans remplacer ce qui est
+    // déjà connu ici. Renvoie le nombre de cellules découvertes.
+    pub fn merge_map(&mut self, other: &WorldMap, (dx, dy): Position) -> usize {
+        let shift = |(x, y): Position| (x + dx, y + dy);
+        let before = self.cells.len();
+        for (position, cell) in &other.cells {
+            self.cells.entry(shift(*position)).or_insert(*cell);
+        }
+        for ((position, direction), passage) in &other.walls {
+            self.walls.entry((shift(*position), *direction)).or_insert(*passage);
+        }
+        for (direction, edge) in &other.borders {
+            let shift = match direction {
+                Direction::West | Direction::East => dx,
+                Direction::North | Direction::South => dy,
+            };
+            self.borders.insert((*direction, edge + shift));
+        }
+        if self.grid_size.is_none() {
+            self.grid_size = other.grid_size;
+        }
+        self.cells.len() - before
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (Position, RadarCell)> + '_ {
+        self.cells.iter().map(|(position, cell)| (*position, *cell))
+    }
+
+    // La taille vient du serveur : elle est refusée si elle est nulle ou démesurée
+    pub fn set_grid_size(&mut self, columns: u32, rows: u32) -> Result<(), String> {
+        if columns == 0 || rows == 0 || columns > MAX_GRID_SIZE || rows > MAX_GRID_SIZE {
+            return Err(format!(
+                "Taille de grille invalide : {}x{} (1 à {} cellules par côté)",
+                columns, rows, MAX_GRID_SIZE
+            ));
+        }
+        self.grid_size = Some((columns, rows));
+        Ok(())
+    }
+
+    pub fn grid_size(&self) -> Option<(u32, u32)> {
+        self.grid_size
+    }
+
+    // Nombre de cellules de la grille le long des axes x et y de la carte. Le joueur a pu partir tourné
+    // vers n'importe quel côté : colonnes et lignes ne sont attribuées aux axes que si la carte n'entre
+    // dans la grille que d'une seule façon. None si la taille est inconnue ou l'attribution ambiguë.
+    pub fn grid_spans(&self) -> Option<(u32, u32)> {
+        let (columns, rows) = self.grid_size?;
+        if columns == rows {
+            return Some((columns, rows));
+        }
+        let ((min_x, min_y), (max_x, max_y)) = self.inside_bounds();
+        let fits = |(span_x, span_y): (u32, u32)| max_x - min_x < span_x as i32 && max_y - min_y < span_y as i32;
+        match (fits((columns, rows)), fits((rows, columns))) {
+            (true, false) => Some((columns, rows)),
+            (false, true) => Some((rows, columns)),
+            _ => None,
+        }
+    }
+
+    // Décalages compatibles avec tout ce qui a été vu : chaque cellule connue, et chaque cellule
+    // derrière un passage ouvert, est dans la grille. Un bord fixe à lui seul le décalage de son axe,
+    // s'il est compatible avec la carte et ne contredit pas le bord opposé.
+    // None si la taille est inconnue.
+    pub fn grid_offsets(&self) -> Option<GridOffsets> {
+        let spans = self.grid_spans();
+        let (span_x, span_y) = self.span_bounds()?;
+        let ((min_x, min_y), (max_x, max_y)) = self.inside_bounds();
+        let mut offsets = GridOffsets {
+            x: (-min_x, span_x - 1 - max_x),
+            y: (-min_y, span_y - 1 - max_y),
+        };
+
+        // Décalage donné par le candidat compatible d'un côté. Chaque candidat est sur une cellule connue :
+        // seul celui qui est à l'extrémité de la carte de ce côté peut être compatible.
+        let border_offset = |side: Direction, range: (i32, i32)| {
+            self.borders
+                .iter()
+                .filter(|(direction, _)| *direction == side)
+                .filter_map(|(_, edge)| match side {
+                    Direction::West | Direction::North => Some(-edge),
+                    Direction::East => spans.map(|(span_x, _)| span_x as i32 - 1 - edge),
+                    Direction::South => spans.map(|(_, span_y)| span_y as i32 - 1 - edge),
+                })
+                .find(|offset| range.0 <= *offset && *offset <= range.1)
+        };
+        let fix = |range: &mut (i32, i32), low_side, high_side| {
+            let offset = match (border_offset(low_side, *range), border_offset(high_side, *range)) {
+                // Bords opposés contradictoires : aucun n'est retenu
+                (Some(low), Some(high)) if low != high => None,
+                (low, high) => low.or(high),
+            };
+            if let Some(offset) = offset {
+                *range = (offset, offset);
+            }
+        };
+        fix(&mut offsets.x, Direction::West, Direction::East);
+        fix(&mut offsets.y, Direction::North, Direction::South);
+        Some(offsets)
+    }
+
+    // Décalage exact, une fois les deux axes fixés par un bord ou par
+    // une carte aussi large ou aussi haute que la grille
+    pub fn grid_offset(&self) -> Option<Position> {
+        let offsets = self.grid_offsets()?;
+        Some((offsets.fixed_x()?, offsets.fixed_y()?))
+    }
+
+    // Vrai si toutes les cellules connues, et celles derrière un passage ouvert, sont dans la grille
+    // une fois la carte placée avec ce décalage
+    pub fn fits_grid(&self, (dx, dy): Position) -> bool {
+        let Some((span_x, span_y)) = self.span_bounds() else {
+            return true;
+        };
+        self.inside_positions()
+            .all(|(x, y)| (0..span_x).contains(&(x + dx)) && (0..span_y).contains(&(y + dy)))
+    }
+
+    // Taille de la grille le long des axes de la carte, la plus grande des deux quand elle est ambiguë
+    fn span_bounds(&self) -> Option<(i32, i32)> {
+        let (columns, rows) = self.grid_size?;
+        let largest = columns.max(rows);
+        let (span_x, span_y) = self.grid_spans().unwrap_or((largest, largest));
+        Some((span_x as i32, span_y as i32))
+    }
+
+    // Positions forcément dans la grille : cellules connues et cellules derrière un passage ouvert
+    fn inside_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells
+            .keys()
+            .copied()
+            .chain(self.cells.keys().flat_map(|position| self.neighbors(*position)).map(|(_, position)| position))
+    }
+
+    // Plus petit rectangle contenant inside_positions et l'origine : (min, max)
+    fn inside_bounds(&self) -> (Position, Position) {
+        self.inside_positions().fold(((0, 0), (0, 0)), |(min, max), (x, y)| {
+            ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))
+        })
+    }
+
+    // Décalage qui superpose cette carte à `reference` (déjà dans le repère de la grille), choisi parmi
+    // les décalages encore possibles : aucun passage ouvert d'un côté et fermé de l'autre, et assez de
+    // passages en commun. None si aucun décalage, ou plus d'un, ne convient.
+    pub fn align_with(&self, reference: &WorldMap) -> Option<Position> {
+        let offsets = self.grid_offsets()?;
+        let candidates = (offsets.x.1 - offsets.x.0 + 1) as i64 * (offsets.y.1 - offsets.y.0 + 1) as i64;
+        if offsets.x.0 > offsets.x.1 || offsets.y.0 > offsets.y.1 || candidates > MAX_ALIGNMENT_CANDIDATES {
+            return None;
+        }
+        let mut aligned = None;
+        for dx in offsets.x.0..=offsets.x.1 {
+            for dy in offsets.y.0..=offsets.y.1 {
+                let mut common = 0;
+                let consistent = self.walls.iter().all(|(((x, y), direction), passage)| {
+                    match reference.walls.get(&((x + dx, y + dy), *direction)) {
+                        Some(other) => {
+                            common += 1;
+                            other == passage
+                        }
+                        None => true,
+                    }
+                });
+                if consistent && common >= MIN_ALIGNMENT_WALLS {
+                    if aligned.is_some() {
+                        return None;
+                    }
+                    aligned = Some((dx, dy));
+                }
+            }
+        }
+        aligned
+    }
+
+    // Position depuis le coin de la grille au nord-ouest de la carte, si elle est connue
+    pub fn grid_position(&self, (x, y): Position) -> Option<Position> {
+        let (dx, dy) = self.grid_offset()?;
+        Some((x + dx, y + dy))
+    }
+
+    // Faux seulement si la position est hors de la grille quel que soit le décalage possible
+    pub fn may_be_inside(&self, (x, y): Position) -> bool {
+        let (Some((span_x, span_y)), Some(offsets)) = (self.span_bounds(), self.grid_offsets()) else {
+            return true;
+        };
+        let on_axis = |value: i32, (low, high): (i32, i32), span: i32| value + high >= 0 && value + low < span;
+        on_axis(x, offsets.x, span_x) && on_axis(y, offsets.y, span_y)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn is_known(&self, position: Position) -> bool {
+        self.cells.contains_key(&position)
+    }
+
+    pub fn cell(&self, position: Position) -> RadarCell {
+        self.cells.get(&position).copied().unwrap_or(RadarCell::Undefined)
+    }
+
+    pub fn passage(&self, position: Position, direction: Direction) -> Passage {
+        self.walls.get(&wall_key(position, direction)).copied().unwrap_or(Passage::Undefined)
+    }
+
+    pub fn is_open(&self, position: Position, direction: Direction) -> bool {
+        self.passage(position, direction) == Passage::Open
+    }
+
+    // Cellules voisines accessibles par un passage ouvert, connues ou non
+    pub fn neighbors(&self, position: Position) -> impl Iterator<Item = (Direction, Position)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter(move |direction| self.is_open(position, *direction))
+            .map(move |direction| (direction, step(position, direction)))
+    }
+
+    pub fn exits(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells
+            .iter()
+            .filter(|(_, cell)| cell.is_goal())
+            .map(|(position, _)| *position)
+    }
+
+    // Zones encore inexplorées : cellules jamais vues derrière un passage ouvert,
+    // sans celles qui ne peuvent pas être dans la grille
+    pub fn unexplored(&self) -> Vec<Position> {
+        let mut unexplored: Vec<Position> = self
+            .cells
+            .keys()
+            .flat_map(|position| self.neighbors(*position))
+            .map(|(_, position)| position)
+            .filter(|position| !self.is_known(*position) && self.may_be_inside(*position))
+            .collect();
+        unexplored.sort();
+        unexplored.dedup();
+        unexplored
+    }
+
+    // Plus petit rectangle contenant toutes les cellules connues : (min, max)
+    pub fn bounds(&self) -> Option<(Position, Position)> {
+        let (xs, ys): (Vec<i32>, Vec<i32>) = self.cells.keys().copied().unzip();
+        Some((
+            (*xs.iter().min()?, *ys.iter().min()?),
+            (*xs.iter().max()?, *ys.iter().max()?),
+        ))
+    }
+}
+
+// Un mur sud ou est est le mur nord ou ouest de la cellule voisine
+fn wall_key(position: Position, direction: Direction) -> (Position, Direction) {
+    match direction {
+        Direction::North | Direction::West => (position, direction),
+        Direction::South | Direction::East => (step(position, direction), direction.opposite()),
+    }
+}
+
+// Même format que l'affichage du radar et de Maze, le nord en haut
+impl fmt::Display for WorldMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() else {
+            return writeln!(f, "(carte vide)");
+        };
+        for row in 0..=2 * (max_y - min_y + 1) {
+            let line: Vec<String> = (0..=2 * (max_x - min_x + 1))
+                .map(|column| {
+                    let position = (min_x + column / 2, min_y + row / 2);
+                    let cell = match (row % 2, column % 2) {
+                        (0, 0) => Cell::Undefined,
+                        (1, 1) => Cell::from(self.cell(position)),
+                        (0, _) => Cell::from(self.passage(position, Direction::North)),
+                        _ => Cell::from(self.passage(position, Direction::West)),
+                    };
+                    cell.to_string()
+                })
+                .collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use crate::radar_module::{neighbour, parse_ascii_grid, Cell, CellNature, Occupant, Passage, RadarCell, RadarView};
+use crate::direction_module::{Direction, Heading};
+use crate::protocol_module::RelativeDirection;
+
+// Labyrinthe complet, vu du dessus avec le nord en haut.
+// Les murs horizontaux sont indexés [y][x] avec y de 0 à height (le mur au-dessus de la ligne y),
+// les murs verticaux [y][x] avec x de 0 à width (le mur à gauche de la colonne x).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    width: usize,
+    height: usize,
+    horizontal_walls: Vec<Vec<Passage>>,
+    vertical_walls: Vec<Vec<Passage>>,
+    cells: Vec<Vec<RadarCell>>,
+}
+
+impl Maze {
+    // Labyrinthe sans mur intérieur, entouré d'un mur
+    pub fn new(width: usize, height: usize) -> Self {
+        let horizontal_walls = (0..=height)
+            .map(|y| {
+                let passage = if y == 0 || y == height { Passage::Wall } else { Passage::Open };
+                vec![passage; width]
+            })
+            .collect();
+        let vertical_walls = (0..height)
+            .map(|_| {
+                (0..=width)
+                    .map(|x| if x == 0 || x == width { Passage::Wall } else { Passage::Open })
+                    .collect()
+            })
+            .collect();
+        let empty = RadarCell::Visible { nature: CellNature::Nothing, occupant: Occupant::Nobody };
+        Maze {
+            width,
+            height,
+            horizontal_walls,
+            vertical_walls,
+            cells: vec![vec![empty; width]; height],
+        }
+    }
+
+    // Labyrinthe écrit avec les symboles du radar : (2 * height + 1) lignes de (2 * width + 1) symboles,
+    // murs et cellules alternés, les coins sont ignorés. Par exemple, 2 cellules séparées par un mur :
+    //   W W W W W
+    //   W O W E W
+    //   W W W W W
+    pub fn from_ascii(text: &str) -> Result<Self, String> {
+        let grid = parse_ascii_grid(text)?;
+        let rows = grid.len();
+        let columns = grid.first().map_or(0, Vec::len);
+        if rows < 3 || columns < 3 || rows % 2 == 0 || columns % 2 == 0 {
+            return Err(format!("Labyrinthe de {}x{} symboles : dimensions impaires d'au moins 3 attendues", columns, rows));
+        }
+        if let Some(row) = grid.iter().position(|row| row.len() != columns) {
+            return Err(format!("Ligne {} de {} symboles au lieu de {}", row + 1, grid[row].len(), columns));
+        }
+
+        let mut maze = Maze::new(columns / 2, rows / 2);
+        for (row, symbols) in grid.iter().enumerate() {
+            for (column, symbol) in symbols.iter().enumerate() {
+                let invalid = |expected: &str| {
+                    format!("'{}' en ligne {}, colonne {} : {} attendu", symbol, row + 1, column + 1, expected)
+                };
+                let (x, y) = (column / 2, row / 2);
+                match (row % 2, column % 2) {
+                    (0, 0) => {}
+                    (1, 1) => {
+                        maze.cells[y][x] = symbol.to_radar_cell().ok_or_else(|| invalid("une cellule"))?;
+                    }
+                    (0, _) => {
+                        maze.horizontal_walls[y][x] = symbol.to_passage().ok_or_else(|| invalid("un mur"))?;
+                    }
+                    _ => {
+                        maze.vertical_walls[y][x] = symbol.to_passage().ok_or_else(|| invalid("un mur"))?;
+                    }
+                }
+            }
+        }
+        Ok(maze)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn cell(&self, x: i32, y: i32) -> RadarCell {
+        if self.contains(x, y) {
+            self.cells[y as usize][x as usize]
+        } else {
+            RadarCell::Undefined
+        }
+    }
+
+    pub fn set_cell(&mut self, x: usize, y: usize, cell: RadarCell) -> &mut Self {
+        self.cells[y][x] = cell;
+        self
+    }
+
+    // Mur au-dessus de la cellule (x, y) ; y = height désigne le bord inférieur
+    pub fn horizontal_wall(&self, x: i32, y: i32) -> Passage {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize > self.height {
+            return Passage::Undefined;
+        }
+        self.horizontal_walls[y as usize][x as usize]
+    }
+
+    // Mur à gauche de la cellule (x, y) ; x = width désigne le bord droit
+    pub fn vertical_wall(&self, x: i32, y: i32) -> Passage {
+        if x < 0 || y < 0 || x as usize > self.width || y as usize >= self.height {
+            return Passage::Undefined;
+        }
+        self.vertical_walls[y as usize][x as usize]
+    }
+
+    // Passage sur le côté `direction` de la cellule (x, y)
+    pub fn passage(&self, x: i32, y: i32, direction: Direction) -> Passage {
+        match direction {
+            Direction::North => self.horizontal_wall(x, y),
+            Direction::South => self.horizontal_wall(x, y + 1),
+            Direction::West => self.vertical_wall(x, y),
+            Direction::East => self.vertical_wall(x + 1, y),
+        }
+    }
+
+    pub fn set_horizontal_wall(&mut self, x: usize, y: usize, passage: Passage) -> &mut Self {
+        self.horizontal_walls[y][x] = passage;
+        self
+    }
+
+    pub fn set_vertical_wall(&mut self, x: usize, y: usize, passage: Passage) -> &mut Self {
+        self.vertical_walls[y][x] = passage;
+        self
+    }
+
+    // Radar vu depuis la cellule (x, y) par un joueur tourné vers le nord, comme le serveur le construit :
+    // les cellules derrière un mur sont hors de vue, de même que les murs qui ne bordent aucune cellule vue.
+    // Une cellule vue en diagonale ne montre que ses murs vers des cellules vues ou vers l'extérieur du radar.
+    pub fn radar_view(&self, x: i32, y: i32) -> RadarView {
+        let visible = self.visible_from(x, y);
+        let in_radar = |(dx, dy): (i32, i32)| dx.abs() <= 1 && dy.abs() <= 1;
+        let is_visible = |(dx, dy): (i32, i32)| in_radar((dx, dy)) && visible[(dy + 1) as usize][(dx + 1) as usize];
+        let shows_wall = |side: (i32, i32), other: (i32, i32)| {
+            is_visible(side) && (side.0 == 0 || side.1 == 0 || is_visible(other) || !in_radar(other))
+        };
+
+        let mut radar = RadarView::new();
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (cx, cy) = (x + dx, y + dy);
+                if is_visible((dx, dy)) {
+                    radar.set_cell(dx, dy, self.cell(cx, cy));
+                }
+                let walls = [
+                    (RelativeDirection::Front, self.horizontal_wall(cx, cy)),
+                    (RelativeDirection::Back, self.horizontal_wall(cx, cy + 1)),
+                    (RelativeDirection::Left, self.vertical_wall(cx, cy)),
+                    (RelativeDirection::Right, self.vertical_wall(cx + 1, cy)),
+                ];
+                for (direction, passage) in walls {
+                    let other = neighbour((dx, dy), direction);
+                    if shows_wall((dx, dy), other) || shows_wall(other, (dx, dy)) {
+                        radar.set_wall((dx, dy), direction, passage);
+                    }
+                }
+            }
+        }
+        radar
+    }
+
+    // Cellules du radar en (x, y) visibles par le joueur, [dy + 1][dx + 1] : ses voisines derrière un passage
+    // ouvert, et les cellules en diagonale ouvertes sur l'une de ces voisines
+    fn visible_from(&self, x: i32, y: i32) -> [[bool; 3]; 3] {
+        let open = |(x, y): (i32, i32), direction| self.passage(x, y, direction) == Passage::Open;
+        let mut visible = [[false; 3]; 3];
+        visible[1][1] = true;
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.offset();
+            visible[(dy + 1) as usize][(dx + 1) as usize] = open((x, y), direction);
+        }
+        for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+            let vertical = if dy < 0 { Direction::North } else { Direction::South };
+            let horizontal = if dx < 0 { Direction::West } else { Direction::East };
+            visible[(dy + 1) as usize][(dx + 1) as usize] = (visible[1][(dx + 1) as usize] && open((x + dx, y), vertical))
+                || (visible[(dy + 1) as usize][1] && open((x, y + dy), horizontal));
+        }
+        visible
+    }
+
+    // Radar tel que le serveur l'envoie à un joueur en (x, y) orienté selon `heading`
+    pub fn radar_view_facing(&self, x: i32, y: i32, heading: Heading) -> RadarView {
+        self.radar_view(x, y).to_relative(heading)
+    }
+}
+
+// Même format que from_ascii, les coins sont affichés comme des murs
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..=2 * self.height {
+            let line: Vec<String> = (0..=2 * self.width)
+                .map(|column| {
+                    let (x, y) = (column / 2, row / 2);
+                    let cell = match (row % 2, column % 2) {
+                        (0, 0) => Cell::Wall,
+                        (1, 1) => Cell::from(self.cells[y][x]),
+                        (0, _) => Cell::from(self.horizontal_walls[y][x]),
+                        _ => Cell::from(self.vertical_walls[y][x]),
+                    };
+                    cell.to_string()
+                })
+                .collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
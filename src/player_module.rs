@@ -1,115 +1,18 @@
-use std::{fmt, io::{self, Read, Write}, net::TcpStream, sync::{Arc, Mutex}, thread};
+use std::{fmt, io::{self, Write}, net::TcpStream, sync::Arc, thread};
 
-use serde::{de::value::Error, Deserialize, Serialize};
 use crate::team_module;
-use crate::communication_module::set_tcp_stream;
+use crate::base64_module;
+use crate::communication_module::{is_connection_closed, is_timeout, set_tcp_stream, FrameCodec, SessionKind};
+use crate::compass_module::{estimate_exit, CompassReading, ExitEstimate};
+use crate::config_module::Config;
+use crate::direction_module::{angular_distance, relative_degrees, Direction, Heading};
+use crate::map_module::{step, Position, WorldMap};
+use crate::radar_module::{neighbour, Offset, Passage, RadarError, RadarView};
+use crate::validation_module::validate_radar;
+use crate::team_map_module::{lock_team_map, SharedTeamMap, TeamMap};
+use crate::protocol_module::{Action, ActionError, ClientMessage, Hint, RegistrationError, RelativeDirection, ServerMessage, SubscribePlayerResult};
 
 
-#[derive(Serialize, Deserialize, Debug)]
-struct SubscribePlayer {
-    name: String,
-    registration_token: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct SubscribePlayerRequest {
-    SubscribePlayer: SubscribePlayer,
-}
-
-enum SubscribePlayerResult{
-     Ok, 
-     Err(String) 
-}
-
-struct Player {
-    id: u32,
-    name: String,
-}
-impl Player {
-    fn new(id: u32, name: String) -> Self {
-        Player { id, name }
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
-pub enum RelativeDirection {
-    Left,
-    Right,
-    Back,
-    Front
-}
-
-impl RelativeDirection {
-    pub fn to_string(&self) -> &str {
-        match self {
-            RelativeDirection::Left   => "Left",
-            RelativeDirection::Right  => "Right",
-            RelativeDirection::Back   => "Back",
-            RelativeDirection::Front  => "Front"
-        }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum Cell {
-        Undefined,                   // 00 
-        Open,                        // 01
-        Wall,                        // 10
-        Exit,                        // 1000 
-        Unknown(String)              // unknown bits combination
-}
-
-impl Cell {
-    pub fn from_bits(bits: &str) -> Self {
-        match bits {
-            "00" | "1111" => Cell::Undefined,
-            "01" | "0000" | "0001" | "0101" => Cell::Open,
-            "10" | "11" | "0111" | "0011" | "0010" | "0110"| "1010" => Cell::Wall,
-            "1000"| "1001" => Cell::Exit,
-            _ => Cell::Unknown(bits.to_string())
-        }
-    }
-}
-impl fmt::Display for Cell {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Cell::Undefined => write!(f, "U"), // Représentation pour Undefined
-            Cell::Open => write!(f, "O"),      // Représentation pour Open
-            Cell::Wall => write!(f, "W"),      // Représentation pour Wall
-            Cell::Exit => write!(f, "E"),      // Représentation pour Exit
-            Cell::Unknown(bits) => write!(f, "?{}", bits), // Représentation pour Unknown
-        }
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum Hint {
-    RelativeCompass { angle: f32 },
-    GridSize { columns: u32, rows: u32 },
-    Secret(u64),
-}
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ActionError {
-    CannotPassThroughWall, 
-    NoRunningChallenge, 
-    SolveChallengeFirst, 
-    InvalidChallengeSolution
-}
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ServerPayload {
-    ActionError(ActionError),
-    RadarView(String),
-    Hint(Hint)
-}
-#[derive(Serialize, Deserialize, Debug)]
-struct Action{
-    MoveTo : RelativeDirection
-}
-#[derive(Serialize, Deserialize, Debug)]
-struct ActionMessage {
-    Action: Action,
-}
-
 pub fn ask_user_for_players(num_players: u32) -> Vec<String> {
     let mut players = Vec::new();
 
@@ -134,17 +37,19 @@ pub fn ask_user_for_players(num_players: u32) -> Vec<String> {
     players
 }
 
-pub fn register_team_and_players() -> std::io::Result<()> {
+pub fn register_team_and_players(config: &Config) -> std::io::Result<()> {
     
     // Demander à l'utilisateur les noms des équipes
     let teams = team_module::ask_user_for_teams();
+    // Tous les joueurs jouent en même temps, chacun dans son thread
+    let mut players_threads = Vec::new();
 
     // Enregistrer chaque équipe et ses joueurs
     for team_name in teams {
         println!("Enregistrement de l'équipe : {}", team_name);
 
         // Enregistrer l'équipe et obtenir le token
-        if let Ok(Some(token)) = team_module::create_team(team_name.clone()) {
+        if let Ok(Some(token)) = team_module::create_team(team_name.clone(), config) {
             println!("Token d'inscription pour l'équipe {} : {}", team_name, token);
 
             // Demander le nombre de joueurs pour cette équipe
@@ -164,11 +69,13 @@ pub fn register_team_and_players() -> std::io::Result<()> {
             // Demander les noms des joueurs
             let players = ask_user_for_players(num_players);
 
-            // Inscrire chaque joueur
+            // Inscrire chaque joueur, tous partagent la même carte d'équipe
+            let team_map = TeamMap::shared();
             for player_name in players {
                 println!("Inscription du joueur {} dans l'équipe {}...", player_name, team_name);
-                if let Err(err) = subscribe_player( player_name, token.clone()) {
-                    println!("Erreur lors de l'inscription du joueur : {}", err);
+                match subscribe_player(player_name, token.clone(), Arc::clone(&team_map), config) {
+                    Ok(play) => players_threads.push(play),
+                    Err(err) => println!("Erreur lors de l'inscription du joueur : {}", err),
                 }
             }
         } else {
@@ -176,462 +83,471 @@ pub fn register_team_and_players() -> std::io::Result<()> {
         }
     }
 
+    for play in players_threads {
+        if play.join().is_err() {
+            eprintln!("Le thread du joueur s'est arrêté brutalement");
+        }
+    }
+
     Ok(())
 }
 
 
 
 
-// Fonction pour inscrire un joueur
-pub fn subscribe_player(name: String, registration_token: String) -> std::io::Result<()> {
+// Issue de la session d'un joueur
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionOutcome {
+    Finished,                          // Sortie atteinte
+    Rejected(RegistrationError),       // Inscription refusée par le serveur
+    Disconnected(String),              // Connexion fermée ou perdue
+    TimedOut,                          // Pas de réponse du serveur dans les délais
+    ProtocolError(String),             // Trame tronquée ou message inattendu
+}
 
-    let stream = set_tcp_stream()?;
-    // Créer un thread pour gérer l'inscription du joueur
-    let play=thread::spawn(move || {
-        if let Err(err) = handle_player(stream, name, registration_token) {
-            eprintln!("Erreur lors de l'inscription du joueur : {}", err);
-           
+impl From<io::Error> for SessionOutcome {
+    fn from(err: io::Error) -> Self {
+        if is_connection_closed(&err) {
+            SessionOutcome::Disconnected(err.to_string())
+        } else if is_timeout(&err) {
+            SessionOutcome::TimedOut
+        } else {
+            match err.kind() {
+                io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData => {
+                    SessionOutcome::ProtocolError(err.to_string())
+                }
+                _ => SessionOutcome::Disconnected(err.to_string()),
+            }
         }
-    });
-    play.join();
-    Ok(())
-
-  
+    }
 }
 
+impl fmt::Display for SessionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionOutcome::Finished => write!(f, "sortie atteinte"),
+            SessionOutcome::Rejected(reason) => write!(f, "inscription refusée ({})", reason),
+            SessionOutcome::Disconnected(reason) => write!(f, "déconnecté ({})", reason),
+            SessionOutcome::TimedOut => write!(f, "délai d'attente dépassé"),
+            SessionOutcome::ProtocolError(reason) => write!(f, "erreur de protocole ({})", reason),
+        }
+    }
+}
 
-fn handle_player(mut stream: TcpStream, name: String, registration_token: String) -> io::Result<()> {
-    // Envoyer la requête d'inscription
-    let request = SubscribePlayerRequest {
-        SubscribePlayer: SubscribePlayer {
-            name: name.clone(),
-            registration_token: registration_token.clone(),
-        },
-    };
-
-    // Sérialiser la requête en JSON
-    let serialized = serde_json::to_string(&request)?;
+impl SessionOutcome {
+    // Une session interrompue par le réseau peut être reprise par une reconnexion
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, SessionOutcome::Disconnected(_) | SessionOutcome::TimedOut)
+    }
+}
 
-    // Envoyer la longueur du message (4 bytes)
-    let length = serialized.len() as u32;
-    stream.write_all(&length.to_le_bytes())?;
+// État d'un joueur conservé d'une connexion à l'autre
+#[derive(Debug, Clone)]
+pub struct PlayerState {
+    pub name: String,
+    pub registration_token: String,
+    pub tracker: MoveTracker,
+    pub map: WorldMap,
+    // Toutes les indications de boussole reçues, avec la position où elles ont été mesurées
+    pub compass: Vec<CompassReading>,
+    // Carte commune aux joueurs de l'équipe, et position de l'origine de la carte du joueur dans celle-ci
+    // une fois sa carte versée
+    pub team_map: Option<SharedTeamMap>,
+    pub team_offset: Option<Position>,
+}
 
-    // Envoyer le message JSON
-    stream.write_all(serialized.as_bytes())?;
+impl PlayerState {
+    pub fn new(name: String, registration_token: String) -> Self {
+        PlayerState {
+            name,
+            registration_token,
+            tracker: MoveTracker::default(),
+            map: WorldMap::new(),
+            compass: Vec::new(),
+            team_map: None,
+            team_offset: None,
+        }
+    }
 
-    // Lire la réponse du serveur
-    let mut payload_size_buffer = [0u8; 4];
-    stream.read_exact(&mut payload_size_buffer).unwrap();
-    let payload_size = u32::from_le_bytes(payload_size_buffer) as usize;
+    pub fn with_team_map(mut self, team_map: SharedTeamMap) -> Self {
+        self.team_map = Some(team_map);
+        self
+    }
 
-    let mut response = vec![0u8; payload_size];
-    stream.read_exact(&mut response).unwrap();
-    let server_response = serde_json::to_string(&response);
-    match server_response {
-        Ok(payload)=>{
-            println!("Inscription OK ");
+    // Échanger avec la carte d'équipe, dès que la position de la carte du joueur dans celle-ci est sûre :
+    // verser sa carte la première fois puis chaque nouveau radar, et récupérer ce que les autres ont vu.
+    // Ce qui est versé n'est jamais remplacé, une carte mal placée fausserait celle de toute l'équipe.
+    fn share_with_team(&mut self, radar_view: &RadarView) {
+        let Some(team_map) = &self.team_map else {
+            return;
+        };
+        let mut team = lock_team_map(team_map);
+        if let Some(offset) = self.team_offset {
+            let (x, y) = self.tracker.position();
+            team.merge_radar(&self.name, radar_view, (x + offset.0, y + offset.1), self.tracker.heading());
+        } else {
+            let Some(offset) = team_placement(&self.map, team.map()) else {
+                return;
+            };
+            let discovered = team.merge_map(&self.name, &self.map, offset);
+            println!("{} partage sa carte avec son équipe ({} nouvelles cellules)", self.name, discovered);
+            self.team_offset = Some(offset);
         }
-        Err(error)=>{
-            eprintln!("Erreur d'inscription : {}", error);
-            return Err(error.into());
+        let Some(offset) = self.team_offset else {
+            return;
+        };
+        let learned = self.map.merge_map(team.map(), (-offset.0, -offset.1));
+        if learned > 0 {
+            println!("{} apprend {} cellules de son équipe", self.name, learned);
         }
     }
-    let mut direction_hint :Option<RelativeDirection> = None;
-    // Boucle pour gérer les interactions du joueur
-    loop {
-        let mut payload_size_buffer = [0u8; 4];
-        stream.read_exact(&mut payload_size_buffer).unwrap();
-        let payload_size = u32::from_le_bytes(payload_size_buffer) as usize;
-
-        let mut response = vec![0u8; payload_size];
-        stream.read_exact(&mut response).unwrap();
-        let server_response= match serde_json::from_slice(&response){
-           
-            Ok(payload) => {
-                payload
-               
-            }
-            Err(err) => {
-                eprintln!("Erreur lors de la lecture des données de {} : {}", name, err);
-                break;
-            }
-        };
-        match server_response {
-            ServerPayload::RadarView(view) => {
-                println!("Message RadarView reçu de {} : {}", name, view);
-                match decoder(&view){
-                    Ok(radar_view)=>{
-                        let direction = move_player(radar_view, direction_hint);
-                        let request = ActionMessage {
-                            Action : Action {
-                                MoveTo: {direction}
-                            }
-                            
-                        };
-                    
-                        // Sérialiser la requête en JSON
-                        let serialized = serde_json::to_string(&request);
-                        match serialized{
-                            Ok(json)=>{
-                                let length = json.len() as u32;
-                                stream.write_all(&length.to_le_bytes())?;
-                    
-                                // Envoyer le message JSON
-                                stream.write_all(json.as_bytes())?;
-                    
-                            }
-                            Err(err)=>{
-                                eprintln!("error : {}", err)
-                            }
-                        }
-                    }
-                    Err(err)=>{
-                       eprintln!("error : {}", err)
-                    }
-                }
-
-            }
-            ServerPayload::Hint(hint) => {
-                println!("Message Hint reçu de {} : {:?}", name, hint);
-                match hint {
-                    Hint::RelativeCompass{ angle } =>{ 
-                        direction_hint = Some(direction_from_angle(angle));  
-                    
-                    }
-                    Hint::GridSize {columns, rows } => {
 
-                    }
-                    Hint::Secret(secret)=>{
-
-                    }
-                    
-                }
-                // Traiter les données binaires
-            }
-            ServerPayload::ActionError(error)=>{
-                println!("Message ActionError reçu de {} : {:?}", name, error);
-            }
+    // Nouvelle session : un MoveTo envoyé avant la coupure n'a jamais été confirmé par un RadarView,
+    // on considère que le joueur n'a pas bougé plutôt que d'appliquer ce déplacement au prochain radar
+    pub fn start_session(&mut self) {
+        if let Some(direction) = self.tracker.roll_back() {
+            println!("Déplacement {} de {} abandonné avec la session précédente", direction, self.name);
         }
-    
     }
 
-    Ok(())
-}
-
-pub fn base64_decode(encoded: &str) -> Result<Vec<u8>, String> {
-    // Table base64
-    let base64_table: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
-        .chars()
-        .collect();
+    // Position de la sortie d'après les indications de boussole, dans le repère de la carte
+    pub fn exit_estimate(&self) -> Option<ExitEstimate> {
+        estimate_exit(&self.compass)
+    }
 
-    // Ignorer les espaces
-    let encoded = encoded.trim();
+    // Cap vers la sortie relatif à l'orientation actuelle du joueur, en degrés (0 devant, 90 à droite).
+    // Calculé vers la sortie estimée si les mesures le permettent, sinon d'après la dernière mesure.
+    pub fn exit_bearing(&self) -> Option<f32> {
+        let (x, y) = self.tracker.position();
+        let estimated = self.exit_estimate().and_then(|estimate| {
+            let (dx, dy) = (estimate.x - x as f64, estimate.y - y as f64);
+            (dx.hypot(dy) >= 0.5).then(|| dx.atan2(-dy).to_degrees() as f32)
+        });
+        let bearing = estimated.or_else(|| self.compass.last().map(CompassReading::bearing))?;
+        Some((bearing - self.tracker.heading().facing().degrees()).rem_euclid(360.0))
+    }
+}
 
-    // Calculer le nombre de caractères de padding manquants
-    let padding_len = match encoded.len() % 4 {
-        0 => 0,      // Aucun padding nécessaire
-        2 => 2,      // 2 caractères de padding nécessaires
-        3 => 1,      // 1 caractère de padding nécessaire
-        _ => return Err("Chaîne base64 invalide : longueur incorrecte".to_string()), // Longueur invalide
+// Position de la carte d'un joueur dans celle de l'équipe. Le décalage du joueur dans la grille doit contenir
+// toute sa carte et, si l'équipe a déjà une carte, être confirmé en superposant les deux cartes.
+// Sans décalage dans la grille, la superposition seule peut placer le joueur.
+fn team_placement(map: &WorldMap, team: &WorldMap) -> Option<Position> {
+    let aligned = if team.is_empty() { None } else { map.align_with(team) };
+    let offset = match map.grid_offset() {
+        Some(offset) if team.is_empty() || aligned == Some(offset) => offset,
+        Some(_) => return None,
+        None => aligned?,
     };
+    map.fits_grid(offset).then_some(offset)
+}
 
-    // Convertir chaque caractère en son index dans la table base64
-    let indices: Vec<u8> = encoded
-        .chars()
-        .filter(|&c| c != '=') // Ignorer les caractères de padding
-        .map(|c| {
-            base64_table
-                .iter()
-                .position(|&x| x == c)
-                .map(|index| index as u8) // Convertir l'index en u8
-                .ok_or_else(|| format!("Caractère invalide dans la chaîne base64 : {}", c))
-        })
-        .collect::<Result<Vec<u8>, String>>()?;
+// Position et orientation du joueur dans le repère de la carte, à l'estime :
+// un MoveTo n'est pris en compte qu'une fois confirmé par le RadarView suivant,
+// et il est annulé si le serveur répond par une ActionError
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoveTracker {
+    position: Position,
+    heading: Heading,
+    pending: Option<RelativeDirection>,
+}
 
-    // Ajouter des indices virtuels pour le padding manquant
-    let mut indices_padded = indices.clone();
-    for _ in 0..padding_len {
-        indices_padded.push(0); // Ajouter des 0 pour simuler le padding
+impl MoveTracker {
+    pub fn position(&self) -> Position {
+        self.position
     }
 
-    // Regrouper les indices en blocs de 4 caractères
-    let mut decoded = Vec::new();
-    for chunk in indices_padded.chunks(4) {
-        // Convertir chaque bloc de 4 caractères en 3 octets
-        let byte1 = (chunk[0] << 2) | (chunk[1] >> 4);
-        let byte2 = ((chunk[1] & 0x0F) << 4) | (chunk[2] >> 2);
-        let byte3 = ((chunk[2] & 0x03) << 6) | chunk[3];
-
-        decoded.push(byte1);
-        decoded.push(byte2);
-        decoded.push(byte3);
+    pub fn heading(&self) -> Heading {
+        self.heading
     }
 
-    // Retirer les octets de padding si nécessaire
-    if padding_len > 0 {
-        decoded.truncate(decoded.len() - padding_len);
+    pub fn pending(&self) -> Option<RelativeDirection> {
+        self.pending
     }
 
-    Ok(decoded)
-}
+    // Un MoveTo vient d'être envoyé
+    pub fn start_move(&mut self, direction: RelativeDirection) {
+        self.pending = Some(direction);
+    }
 
-fn to_binary(bytes: &[u8]) -> Vec<String> {
-    bytes
-        .iter()
-        .map(|byte| format!("{:08b}", byte)) // Convertir chaque octet en binaire sur 8 bits
-        .collect()
-}
+    // Un RadarView est arrivé : le déplacement en attente a eu lieu
+    pub fn confirm(&mut self) -> Option<Direction> {
+        let direction = self.pending.take()?;
+        let absolute = self.heading.record_move(direction);
+        self.position = step(self.position, absolute);
+        Some(absolute)
+    }
 
-fn to_hex(bytes: &[u8]) -> Vec<String> {
-    
-    println!("{:?}", bytes);
-    bytes
-        .iter()
-        .map(|byte| format!("{:02x}", byte)) // Convertir chaque octet en hexadécimal sur 2 caractères
-        .collect()
+    // Le déplacement en attente a été refusé : le joueur n'a pas bougé
+    pub fn roll_back(&mut self) -> Option<RelativeDirection> {
+        self.pending.take()
+    }
 }
-fn get_first_9_hex_chars_from_array(hex_array: &[String]) -> String {
-    // Concaténer toutes les chaînes du tableau
-    let concatenated: String = hex_array.concat();
-    println!("hexa : {}", concatenated);
-   
-    // Extraire les 9 premiers caractères
-    concatenated[..9].to_string()
 
-}
+// Fonction pour inscrire un joueur : la partie se joue dans un thread, à attendre par l'appelant
+pub fn subscribe_player(
+    name: String,
+    registration_token: String,
+    team_map: SharedTeamMap,
+    config: &Config,
+) -> std::io::Result<thread::JoinHandle<()>> {
 
-fn hex_to_binary(hex: &str) -> Vec<Vec<String>>{
-    let cells: Vec<String>=hex.chars()
-        .map(|c| {
-            // Convertir le caractère hexadécimal en valeur numérique
-            let value = u8::from_str_radix(&c.to_string(), 16).unwrap();
-            // Convertir la valeur numérique en chaîne binaire
-            format!("{:04b}", value) // 4 bits par caractère hexadécimal
-        })
-        .collect();
+    let stream = set_tcp_stream(&config.server)?;
+    let config = config.clone();
+    // Créer un thread pour gérer l'inscription du joueur
+    let play=thread::spawn(move || {
+        let mut state = PlayerState::new(name, registration_token).with_team_map(team_map);
+        let outcome = play_with_reconnect(stream, &mut state, &config);
+        println!("Fin de partie pour {} : {}", state.name, outcome);
+    });
+    Ok(play)
 
-    let mut radar_cells: Vec<Vec<String>> = vec![];
 
-    for i in 0..3 {
-        let row = cells[i * 3..(i + 1) * 3].to_vec(); // Extraire chaque ligne
-        radar_cells.push(row);
-    }
-    radar_cells
-    
 }
-fn decoder(encoded_str:  &str  )->Result<(Vec<Vec<Cell>>), String>{
-
-    match base64_decode(encoded_str) {
-        Ok(decoded_bytes) => {
-            println!("Octets décodés : {:?}", decoded_bytes);
-
-            // Séparer les octets en deux groupes
-            let (first_six_bytes, remaining_bytes) = decoded_bytes.split_at(6);
-            
-            // Convertir les 6 premiers octets en binaire
-            let (first_half, second_half) = split_line_column(&to_binary(first_six_bytes));
-            println!("Représentation binaire (6 premiers octets) : {:?} , {:?}", first_half, second_half);
-
-            // Convertir les 5 octets suivants en hexadécimal
-            let hex = hex_to_binary(&get_first_9_hex_chars_from_array(&to_hex(remaining_bytes)));
-            
-            println!("Représentation binaire (5 octets suivants) : {:?}", hex);
-            
-            let radar_view=create_radar_view(first_half, second_half,hex);
-            display_radar_view(&radar_view);
-            Ok(radar_view)
-        }
-        Err(err) => {
-            eprintln!("Erreur lors du décodage base64 : {}", err);
-            Err(err)
+
+// Jouer jusqu'à une issue définitive, en se reconnectant après une coupure réseau
+pub fn play_with_reconnect(stream: TcpStream, state: &mut PlayerState, config: &Config) -> SessionOutcome {
+    let reconnect = &config.server.reconnect;
+    let codec = FrameCodec::for_session(config.record_dir.as_deref(), SessionKind::Player, &state.name);
+    let mut stream = Some(stream);
+    let mut reconnects = 0;
+    loop {
+        // Première session sur la connexion initiale, puis nouvelle connexion à chaque reprise
+        // Le compteur ne porte que sur les coupures sans progrès : il ne repart de zéro que si la session
+        // a reçu au moins un radar, pour qu'un serveur qui accepte l'inscription puis coupe ne fasse pas boucler
+        let outcome = match stream.take().map_or_else(|| set_tcp_stream(&config.server), Ok) {
+            Ok(mut stream) => match subscribe_session(&mut stream, state, &codec) {
+                Ok(()) => {
+                    state.start_session();
+                    let (outcome, radars) = handle_player(stream, state, &codec);
+                    if radars > 0 {
+                        reconnects = 0;
+                    }
+                    outcome
+                }
+                Err(outcome) => outcome,
+            },
+            Err(err) => err.into(),
+        };
+        if !outcome.is_recoverable() || reconnects >= reconnect.max_attempts {
+            return outcome;
         }
+
+        let delay = reconnect.delay_for(reconnects);
+        reconnects += 1;
+        println!(
+            "Reconnexion du joueur {} ({}/{}) dans {:?} après : {}",
+            state.name, reconnects, reconnect.max_attempts, delay, outcome
+        );
+        thread::sleep(delay);
     }
 }
 
-fn split_line_column(array: &[String]) -> (Vec<String>, Vec<String>) {
-    println!("bits : {:?}", array);
-    // Séparer le tableau en deux tranches de 3 éléments
-    let mut first_half  = array[..3].to_vec();
-    let mut second_half = array[3..].to_vec();
-    first_half.reverse();
-    second_half.reverse();
-    let first_str=first_half.concat();
-    let first_tab: Vec<String>=first_str
-                    .chars()
-                    .collect::<Vec<char>>()
-                    .chunks(6)
-                    .map(|chunk| chunk.iter().collect::<String>())
-                    .collect();
-
-    (first_tab, second_half)
-}
 
-fn reader_column_line(array: Vec<String>)->Vec<Vec<Cell>>{
-    let mut rows:Vec<Vec<Cell>>= vec![];
-    for row in array{
-        let mut row_cell : Vec<Cell> = vec![];
-        let row_vec: Vec<String>=row
-                    .chars()
-                    .collect::<Vec<char>>()
-                    .chunks(2)
-                    .map(|chunk| chunk.iter().collect::<String>())
-                    .collect();
-        
-       
-        for element in row_vec{
-            row_cell.push(Cell::from_bits(element.as_str()));
+// Inscrire le joueur au début d'une session, ou renvoyer l'issue qui l'en empêche
+fn subscribe_session(stream: &mut TcpStream, state: &PlayerState, codec: &FrameCodec) -> Result<(), SessionOutcome> {
+    let name = state.name.as_str();
+    // Envoyer la requête d'inscription
+    let request = ClientMessage::SubscribePlayer {
+        name: name.to_string(),
+        registration_token: state.registration_token.clone(),
+    };
+
+    codec.send_message(stream, &request)?;
 
+    // Lire la réponse du serveur
+    match codec.recv_message::<_, ServerMessage>(stream)? {
+        ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok) => {
+            println!("Inscription du joueur {} OK", name);
+            Ok(())
+        }
+        ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Err(reason)) => {
+            eprintln!("Inscription du joueur {} refusée par le serveur : {}", name, reason);
+            Err(SessionOutcome::Rejected(reason))
         }
-        rows.push(row_cell);
+        other => Err(SessionOutcome::ProtocolError(format!("réponse inattendue à l'inscription : {:?}", other))),
     }
-    rows
-    
 }
 
-fn reader_cell(array:Vec<Vec<String>>)->Vec<Vec<Cell>>{
-    let mut cells:Vec<Vec<Cell>>= vec![];
-    for row in array{
-        let mut row_cell:Vec<Cell>= vec![];
-        for element in row{
-            row_cell.push(Cell::from_bits(element.as_str()));
-
+// Jouer une session jusqu'à son issue, et compter les radars reçus
+fn handle_player(mut stream: TcpStream, state: &mut PlayerState, codec: &FrameCodec) -> (SessionOutcome, usize) {
+    let name = state.name.clone();
+    let mut radars = 0;
+    // Boucle pour gérer les interactions du joueur
+    loop {
+        let server_response = match codec.recv_message::<_, ServerMessage>(&mut stream) {
+            Ok(payload) => payload,
+            Err(err) => {
+                eprintln!("Erreur lors de la lecture des données de {} : {}", name, err);
+                return (err.into(), radars);
+            }
+        };
+        if matches!(server_response, ServerMessage::RadarView(_)) {
+            radars += 1;
+        }
+        match react(state, server_response) {
+            Reaction::Act(action) => {
+                if let Err(err) = codec.send_message(&mut stream, &ClientMessage::Action(action)) {
+                    return (err.into(), radars);
+                }
+            }
+            Reaction::Finished => return (SessionOutcome::Finished, radars),
+            Reaction::Wait => {}
         }
-        cells.push(row_cell);
     }
-    cells
 }
 
-fn create_radar_view(rows: Vec<String>, columns: Vec<String>, cells: Vec<Vec<String>>)->Vec<Vec<Cell>>{
-    let rows_cell= reader_column_line(rows);
-    let columns_cell = reader_column_line(columns);
-    let radar_cell = reader_cell(cells);
-    let mut radar_view: Vec<Vec<Cell>> = vec![vec![Cell::Undefined; 7]; 7];
-    let mut j = 0;
-    for element in columns_cell.clone(){
-        println!("rows {:?}", element)
-    }
-    for i in (1..radar_view.len()).step_by(2) {
-        radar_view[i][0] = columns_cell[j][0].clone();
-        radar_view[i][2] = columns_cell[j][1].clone();
-        radar_view[i][4] = columns_cell[j][2].clone();
-        radar_view[i][6] = columns_cell[j][3].clone();
-        j=j+1;
-    }
-    println!("j {}", j);
-    j=0;
-    for element in rows_cell.clone(){
-        println!("columns {:?}", element)
-    }
-    for i in (0..radar_view.len()).step_by(2){
-       
-        radar_view[i][1] = rows_cell[j][0].clone();
-        radar_view[i][3] = rows_cell[j][1].clone();
-        radar_view[i][5] = rows_cell[j][2].clone();
-        j=j+1;
-    }
-    println!("j {}", j);
-    j=0;
-    for element in radar_cell.clone(){
-        println!("rows {:?}", element)
-    }
-    for i in (1..radar_view.len()).step_by(2){
-        
-        radar_view[i][1] = radar_cell[j][0].clone();
-        radar_view[i][3] = radar_cell[j][1].clone();
-        radar_view[i][5] = radar_cell[j][2].clone();
-        j=j+1;
-    }
-    println!("j {}", j);
-    radar_view
+// Décision prise par le joueur en réponse à un message du serveur
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reaction {
+    Act(Action),    // Action à envoyer au serveur
+    Wait,           // Rien à envoyer, attendre le message suivant
+    Finished,       // Sortie atteinte
 }
 
+// Traiter un message du serveur : utilisé par la boucle de jeu et par le rejeu hors ligne
+pub fn react(state: &mut PlayerState, server_response: ServerMessage) -> Reaction {
+    let name = state.name.as_str();
+    match server_response {
+        ServerMessage::RadarView(view) => {
+            println!("Message RadarView reçu de {} : {}", name, view);
+            match decoder(&view){
+                Ok(radar_view)=>{
+                    print!("{}", radar_view);
+                    if let Some(direction) = state.tracker.confirm() {
+                        println!("{} est en {:?}, tourné vers {}", name, state.tracker.position(), direction);
+                    }
+                    state.map.merge(&radar_view, state.tracker.position(), state.tracker.heading());
+                    state.share_with_team(&radar_view);
+                    let Some(direction) = move_player(&radar_view, state.exit_bearing()) else {
+                        println!("Exit reached! Stopping the game.");
+                        return Reaction::Finished;
+                    };
+                    state.tracker.start_move(direction);
+                    return Reaction::Act(Action::MoveTo(direction));
+                }
+                Err(err)=>{
+                   eprintln!("error : {}", err)
+                }
+            }
 
-fn display_radar_view(radar_view: &Vec<Vec<Cell>>) {
-    for row in radar_view {
-        for cell in row {
-            print!("{} ", cell); // Affiche chaque cellule suivie d'un espace
         }
-        println!(); // Nouvelle ligne après chaque ligne de la matrice
-    }
-}
+        ServerMessage::Hint(hint) => {
+            println!("Message Hint reçu de {} : {:?}", name, hint);
+            match hint {
+                Hint::RelativeCompass{ angle } =>{ 
+                    state.compass.push(CompassReading {
+                        position: state.tracker.position(),
+                        heading: state.tracker.heading(),
+                        angle,
+                    });
+                    if let Some(estimate) = state.exit_estimate() {
+                        println!("Estimation pour {} : {}", name, estimate);
+                    }
+                }
+                Hint::GridSize { columns, rows } => {
+                    if let Err(err) = state.map.set_grid_size(columns, rows) {
+                        eprintln!("Indication ignorée pour {} : {}", name, err);
+                    }
+                }
+                Hint::Secret(_)=>{
 
-fn move_player(radar_view: Vec<Vec<Cell>>, hint : Option<RelativeDirection>)->RelativeDirection{
-    let center = 3;
-    if radar_view[center][center] == Cell::Exit {
-        println!("Exit reached! Stopping the game.");
-        std::process::exit(0);
-    }
-    let right_cell = radar_view[center][center + 2].clone();
-    let front_cell = radar_view[center - 2][center].clone();
-    let left_cell  = radar_view[center][center - 2].clone();
-    let back_cell = radar_view[center + 2][center].clone();
-    let cells = vec![
-        (right_cell.clone(), RelativeDirection::Right),
-        (front_cell.clone(), RelativeDirection::Front),
-        (left_cell.clone(), RelativeDirection::Left),
-        (back_cell.clone(), RelativeDirection::Back)
-    ];
-    match right_cell{
-        Cell::Open=>{
-            println!("right OK");
-        }
-        _ =>{}
+                }
+                Hint::SOSHelper=>{
 
-    }
-    match front_cell{
-        Cell::Open=>{
-            println!("front OK");
+                }
+                
+            }
+            // Traiter les données binaires
         }
-        _ =>{}
-
-    }
-    match left_cell{
-        Cell::Open=>{
-            println!("lzft OK");
+        ServerMessage::ActionError(error)=>{
+            println!("Message ActionError reçu de {} : {:?}", name, error);
+            if let Some(direction) = state.tracker.roll_back() {
+                println!("Déplacement {} de {} annulé", direction, name);
+                // Le mur est maintenant connu, même s'il n'était pas encore sur la carte
+                if error == ActionError::CannotPassThroughWall {
+                    let absolute = state.tracker.heading().to_absolute(direction);
+                    state.map.record_passage(state.tracker.position(), absolute, Passage::Wall);
+                }
+            }
         }
-        _ =>{}
+        ServerMessage::Challenge(challenge)=>{
+            println!("Message Challenge reçu de {} : {:?}", name, challenge);
+        }
+        ServerMessage::RegisterTeamResult(_) | ServerMessage::SubscribePlayerResult(_)=>{
+            println!("Message inattendu reçu de {} : {:?}", name, server_response);
+        }
+    }
+    Reaction::Wait
+}
+
+// Inverse de decoder : la chaîne RadarView telle que le serveur l'envoie
+pub fn encoder(radar_view: &RadarView) -> String {
+    base64_module::encode(&radar_view.to_bytes(), &base64_module::GAME)
+}
 
+pub fn decoder(encoded_str:  &str  )->Result<RadarView, RadarError>{
+    let decoded_bytes = base64_module::decode(encoded_str, &base64_module::GAME).map_err(RadarError::InvalidBase64)?;
+    let radar_view = RadarView::from_bytes(&decoded_bytes)?;
+    for warning in validate_radar(&radar_view) {
+        eprintln!("Avertissement : RadarView {} incohérent : {}", encoded_str, warning);
     }
-    match back_cell{
-        Cell::Open=>{
-            println!("back OK");
-        }
-        _ =>{}
+    Ok(radar_view)
+}
 
+// Choisir la prochaine direction, ou None si le joueur est sur la sortie.
+// `bearing` est le cap vers la sortie relatif au joueur, en degrés (0 devant, 90 à droite).
+pub fn move_player(radar_view: &RadarView, bearing: Option<f32>) -> Option<RelativeDirection> {
+    let center = (0, 0);
+    if radar_view.cell(0, 0).is_goal() {
+        return None;
     }
-    let open: Vec<RelativeDirection> = cells
+    // Une direction est ouverte si le passage est ouvert et la cellule voisine libre
+    let directions = [
+        RelativeDirection::Right,
+        RelativeDirection::Front,
+        RelativeDirection::Left,
+        RelativeDirection::Back,
+    ];
+    let open: Vec<RelativeDirection> = directions
         .into_iter()
-        .filter(|(cell, _)| *cell == Cell::Open)
-        .map(|(_,direction)| direction)
+        .filter(|direction| radar_view.wall(center, *direction) == Passage::Open)
+        .filter(|direction| {
+            let (dx, dy) = neighbour(center, *direction);
+            radar_view.cell(dx, dy).is_walkable()
+        })
         .collect();
-
-    if let Some(direction) = hint {
-        if open.contains(&direction) {
-            return direction
-        }
-    }
-    
-    let result;
-    if open.len()>0{
-        result=open[0];
-    }else{
-        result=RelativeDirection::Right;
+    for direction in &open {
+        println!("{} OK", direction);
     }
 
+    // Aller directement sur la sortie si elle est voisine
+    if let Some(direction) = open.iter().find(|direction| {
+        let (dx, dy) = neighbour(center, **direction);
+        radar_view.cell(dx, dy).is_goal()
+    }) {
+        return Some(*direction);
+    }
 
-    result
-    // Envoyer la longueur du message (4 bytes)
-   
+    let Some(bearing) = bearing else {
+        // Sans boussole : main droite
+        return Some(open.first().copied().unwrap_or(RelativeDirection::Right));
+    };
+    // Passage ouvert le mieux aligné avec la sortie. À égalité : éviter de revenir en arrière,
+    // puis préférer la cellule qui a le plus de passages ouverts (moins de risque d'impasse),
+    // puis l'ordre de la main droite (open est déjà dans cet ordre et min_by garde le premier)
+    let score = |direction: &RelativeDirection| {
+        let distance = angular_distance(bearing, relative_degrees(*direction));
+        let exits = onward_passages(radar_view, neighbour(center, *direction));
+        ((distance * 1000.0).round() as i32, *direction == RelativeDirection::Back, usize::MAX - exits)
+    };
+    let best = open.iter().min_by_key(|direction| score(direction)).copied();
+    Some(best.unwrap_or(RelativeDirection::Right))
 }
 
-fn direction_from_angle(angle: f32) -> RelativeDirection {
-    if angle >= 315.0 || angle < 45.0 {
-        RelativeDirection::Front   // North (0°) ➜ Front
-    } else if angle >= 45.0 && angle < 135.0 {
-        RelativeDirection::Right   // East (90°) ➜ Right
-    } else if angle >= 135.0 && angle < 225.0 {
-        RelativeDirection::Back    // South (180°) ➜ Back
-    } else {
-        RelativeDirection::Left    // West (270°) ➜ Left
-    }
-}
\ No newline at end of file
+// Nombre de passages ouverts visibles autour d'une cellule du radar
+fn onward_passages(radar_view: &RadarView, cell: Offset) -> usize {
+    [RelativeDirection::Front, RelativeDirection::Right, RelativeDirection::Back, RelativeDirection::Left]
+        .into_iter()
+        .filter(|direction| radar_view.wall(cell, *direction) == Passage::Open)
+        .count()
+}
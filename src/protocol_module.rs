@@ -0,0 +1,114 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// Modèle des messages échangés avec le serveur.
+// Toutes les énumérations utilisent la représentation JSON "externally tagged" de serde,
+// qui est exactement celle attendue par le serveur : {"NomDuVariant": contenu}.
+
+// ---------- Messages client -> serveur ----------
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    RegisterTeam { name: String },
+    SubscribePlayer { name: String, registration_token: String },
+    Action(Action),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Action {
+    MoveTo(RelativeDirection),
+    SolveChallenge { answer: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelativeDirection {
+    Left,
+    Right,
+    Back,
+    Front
+}
+
+impl fmt::Display for RelativeDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelativeDirection::Left   => write!(f, "Left"),
+            RelativeDirection::Right  => write!(f, "Right"),
+            RelativeDirection::Back   => write!(f, "Back"),
+            RelativeDirection::Front  => write!(f, "Front")
+        }
+    }
+}
+
+// ---------- Messages serveur -> client ----------
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    RegisterTeamResult(RegisterTeamResult),
+    SubscribePlayerResult(SubscribePlayerResult),
+    RadarView(String),
+    Hint(Hint),
+    Challenge(Challenge),
+    ActionError(ActionError),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RegisterTeamResult {
+    Ok(RegistrationSuccess),
+    Err(RegistrationError),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegistrationSuccess {
+    pub expected_players: u32,
+    pub registration_token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SubscribePlayerResult {
+    Ok,
+    Err(RegistrationError),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationError {
+    AlreadyRegistered,
+    InvalidName,
+    InvalidRegistrationToken,
+    TooManyPlayers,
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::AlreadyRegistered        => write!(f, "déjà enregistré"),
+            RegistrationError::InvalidName              => write!(f, "nom invalide"),
+            RegistrationError::InvalidRegistrationToken => write!(f, "token d'inscription invalide"),
+            RegistrationError::TooManyPlayers           => write!(f, "trop de joueurs"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Hint {
+    RelativeCompass { angle: f32 },
+    GridSize { columns: u32, rows: u32 },
+    Secret(u64),
+    SOSHelper,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Challenge {
+    SecretSumModulo(u64),
+    #[serde(rename = "SOS")]
+    Sos,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    CannotPassThroughWall,
+    CannotPassThroughOpponent,
+    NoRunningChallenge,
+    SolveChallengeFirst,
+    InvalidChallengeSolution
+}
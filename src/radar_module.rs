@@ -0,0 +1,559 @@
+use std::{error::Error, fmt};
+
+use crate::base64_module::Base64Error;
+use crate::direction_module::{rotate_offset, rotate_relative, Heading};
+use crate::protocol_module::RelativeDirection;
+
+// Un RadarView décodé fait exactement 11 octets :
+// 3 octets de murs horizontaux, 3 octets de murs verticaux, 5 octets de cellules
+pub const RADAR_BYTES: usize = 11;
+const HORIZONTAL_WALLS: usize = 0;
+const VERTICAL_WALLS: usize = 3;
+const CELLS: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadarError {
+    InvalidBase64(Base64Error),
+    InvalidLength { expected: usize, actual: usize },
+    InvalidAscii(String),
+}
+
+impl fmt::Display for RadarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadarError::InvalidBase64(err) => write!(f, "RadarView base64 invalide : {}", err),
+            RadarError::InvalidLength { expected, actual } => {
+                write!(f, "RadarView de {} octets au lieu de {}", actual, expected)
+            }
+            RadarError::InvalidAscii(err) => write!(f, "RadarView ASCII invalide : {}", err),
+        }
+    }
+}
+
+impl Error for RadarError {}
+
+// Case de la grille 7x7 (murs et cellules confondus)
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+        Undefined,                   // hors de vue
+        Open,                        // passage ouvert ou cellule vide
+        Wall,                        // mur
+        Exit,                        // cellule de sortie
+        Hint,                        // cellule contenant un indice
+        Ally,                        // cellule occupée par un coéquipier
+        Enemy,                       // cellule occupée par un adversaire
+        Monster,                     // cellule occupée par un monstre
+        Unknown(String)              // unknown bits combination
+}
+
+impl From<RadarCell> for Cell {
+    fn from(cell: RadarCell) -> Self {
+        match cell {
+            RadarCell::Undefined => Cell::Undefined,
+            RadarCell::Invalid(bits) => Cell::Unknown(format!("{:04b}", bits)),
+            RadarCell::Visible { occupant: Occupant::Enemy, .. } => Cell::Enemy,
+            RadarCell::Visible { occupant: Occupant::Monster, .. } => Cell::Monster,
+            RadarCell::Visible { nature: CellNature::Goal, .. } => Cell::Exit,
+            RadarCell::Visible { occupant: Occupant::Ally, .. } => Cell::Ally,
+            RadarCell::Visible { nature: CellNature::Hint, .. } => Cell::Hint,
+            RadarCell::Visible { nature: CellNature::Nothing, occupant: Occupant::Nobody } => Cell::Open,
+        }
+    }
+}
+
+impl From<Passage> for Cell {
+    fn from(passage: Passage) -> Self {
+        match passage {
+            Passage::Undefined => Cell::Undefined,
+            Passage::Open => Cell::Open,
+            Passage::Wall | Passage::Invalid => Cell::Wall,
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Undefined => write!(f, "U"), // Représentation pour Undefined
+            Cell::Open => write!(f, "O"),      // Représentation pour Open
+            Cell::Wall => write!(f, "W"),      // Représentation pour Wall
+            Cell::Exit => write!(f, "E"),      // Représentation pour Exit
+            Cell::Hint => write!(f, "H"),      // Représentation pour Hint
+            Cell::Ally => write!(f, "A"),      // Représentation pour Ally
+            Cell::Enemy => write!(f, "X"),     // Représentation pour Enemy
+            Cell::Monster => write!(f, "M"),   // Représentation pour Monster
+            Cell::Unknown(bits) => write!(f, "?{}", bits), // Représentation pour Unknown
+        }
+    }
+}
+
+impl Cell {
+    // Inverse de Display
+    pub fn from_symbol(symbol: &str) -> Option<Cell> {
+        let cell = match symbol {
+            "U" => Cell::Undefined,
+            "O" => Cell::Open,
+            "W" => Cell::Wall,
+            "E" => Cell::Exit,
+            "H" => Cell::Hint,
+            "A" => Cell::Ally,
+            "X" => Cell::Enemy,
+            "M" => Cell::Monster,
+            _ => {
+                let bits = symbol.strip_prefix('?')?;
+                if bits.len() != 4 || u8::from_str_radix(bits, 2).is_err() {
+                    return None;
+                }
+                Cell::Unknown(bits.to_string())
+            }
+        };
+        Some(cell)
+    }
+
+    // Contenu d'une cellule représenté par ce symbole (None pour un mur)
+    pub fn to_radar_cell(&self) -> Option<RadarCell> {
+        let visible = |nature, occupant| Some(RadarCell::Visible { nature, occupant });
+        match self {
+            Cell::Undefined => Some(RadarCell::Undefined),
+            Cell::Open => visible(CellNature::Nothing, Occupant::Nobody),
+            Cell::Wall => None,
+            Cell::Exit => visible(CellNature::Goal, Occupant::Nobody),
+            Cell::Hint => visible(CellNature::Hint, Occupant::Nobody),
+            Cell::Ally => visible(CellNature::Nothing, Occupant::Ally),
+            Cell::Enemy => visible(CellNature::Nothing, Occupant::Enemy),
+            Cell::Monster => visible(CellNature::Nothing, Occupant::Monster),
+            Cell::Unknown(bits) => u8::from_str_radix(bits, 2).ok().map(RadarCell::from_nibble),
+        }
+    }
+
+    // Passage représenté par ce symbole (None pour le contenu d'une cellule)
+    pub fn to_passage(&self) -> Option<Passage> {
+        match self {
+            Cell::Undefined => Some(Passage::Undefined),
+            Cell::Open => Some(Passage::Open),
+            Cell::Wall => Some(Passage::Wall),
+            _ => None,
+        }
+    }
+}
+
+// Lire une grille de symboles, une ligne de texte par ligne de la grille. Les symboles sont
+// séparés par des espaces comme dans l'affichage du radar, ou collés ("UWU") s'ils font un caractère.
+// Les lignes vides sont ignorées.
+pub fn parse_ascii_grid(text: &str) -> Result<Vec<Vec<Cell>>, String> {
+    let mut grid = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let symbols: Vec<String> = if line.contains(char::is_whitespace) {
+            line.split_whitespace().map(str::to_string).collect()
+        } else {
+            line.chars().map(String::from).collect()
+        };
+        let row = symbols
+            .iter()
+            .map(|symbol| {
+                Cell::from_symbol(symbol)
+                    .ok_or_else(|| format!("ligne {} : symbole inconnu '{}'", number + 1, symbol))
+            })
+            .collect::<Result<Vec<Cell>, String>>()?;
+        grid.push(row);
+    }
+    Ok(grid)
+}
+
+// Passage entre deux cellules, codé sur 2 bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Passage {
+    Undefined,  // 00
+    Open,       // 01
+    Wall,       // 10
+    Invalid,    // 11, ne devrait pas être envoyé par le serveur
+}
+
+impl Passage {
+    pub fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Passage::Undefined,
+            0b01 => Passage::Open,
+            0b10 => Passage::Wall,
+            _ => Passage::Invalid,
+        }
+    }
+
+    pub fn to_bits(self) -> u8 {
+        match self {
+            Passage::Undefined => 0b00,
+            Passage::Open => 0b01,
+            Passage::Wall => 0b10,
+            Passage::Invalid => 0b11,
+        }
+    }
+}
+
+// Nature d'une cellule : 2 bits de poids fort du quartet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellNature {
+    Nothing,    // 00
+    Hint,       // 01
+    Goal,       // 10
+}
+
+// Occupant d'une cellule : 2 bits de poids faible du quartet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occupant {
+    Nobody,     // 00
+    Ally,       // 01
+    Enemy,      // 10
+    Monster,    // 11
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarCell {
+    Undefined,                                          // 1111 : hors de vue
+    Visible { nature: CellNature, occupant: Occupant },
+    Invalid(u8),                                        // nature 11 autre que 1111
+}
+
+impl RadarCell {
+    pub fn from_nibble(bits: u8) -> Self {
+        let bits = bits & 0x0F;
+        let nature = match bits >> 2 {
+            0b00 => CellNature::Nothing,
+            0b01 => CellNature::Hint,
+            0b10 => CellNature::Goal,
+            _ if bits == 0b1111 => return RadarCell::Undefined,
+            _ => return RadarCell::Invalid(bits),
+        };
+        let occupant = match bits & 0b11 {
+            0b00 => Occupant::Nobody,
+            0b01 => Occupant::Ally,
+            0b10 => Occupant::Enemy,
+            _ => Occupant::Monster,
+        };
+        RadarCell::Visible { nature, occupant }
+    }
+
+    pub fn to_nibble(self) -> u8 {
+        match self {
+            RadarCell::Undefined => 0b1111,
+            RadarCell::Invalid(bits) => bits & 0x0F,
+            RadarCell::Visible { nature, occupant } => {
+                let nature = match nature {
+                    CellNature::Nothing => 0b00,
+                    CellNature::Hint => 0b01,
+                    CellNature::Goal => 0b10,
+                };
+                let occupant = match occupant {
+                    Occupant::Nobody => 0b00,
+                    Occupant::Ally => 0b01,
+                    Occupant::Enemy => 0b10,
+                    Occupant::Monster => 0b11,
+                };
+                (nature << 2) | occupant
+            }
+        }
+    }
+
+    pub fn is_goal(&self) -> bool {
+        matches!(self, RadarCell::Visible { nature: CellNature::Goal, .. })
+    }
+
+    // Cellule visible sur laquelle le joueur peut avancer (vide ou occupée par un coéquipier)
+    pub fn is_walkable(&self) -> bool {
+        matches!(self, RadarCell::Visible { occupant: Occupant::Nobody | Occupant::Ally, .. })
+    }
+}
+
+// Position relative au joueur : (0, 0) est la cellule du joueur,
+// dx croît vers la droite et dy vers l'arrière (dy = -1 est la cellule de devant)
+pub type Offset = (i32, i32);
+
+pub fn neighbour((dx, dy): Offset, direction: RelativeDirection) -> Offset {
+    match direction {
+        RelativeDirection::Front => (dx, dy - 1),
+        RelativeDirection::Right => (dx + 1, dy),
+        RelativeDirection::Back  => (dx, dy + 1),
+        RelativeDirection::Left  => (dx - 1, dy),
+    }
+}
+
+// Un mur du radar, rattaché à une cellule visible et au côté de cette cellule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadarWall {
+    pub from: Offset,
+    pub direction: RelativeDirection,
+    pub passage: Passage,
+}
+
+// Radar 3x3 autour du joueur : 4 lignes de 3 murs horizontaux,
+// 3 lignes de 4 murs verticaux et 3x3 cellules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadarView {
+    horizontal_walls: [[Passage; 3]; 4],
+    vertical_walls: [[Passage; 4]; 3],
+    cells: [[RadarCell; 3]; 3],
+}
+
+impl Default for RadarView {
+    fn default() -> Self {
+        RadarView::new()
+    }
+}
+
+impl RadarView {
+    // Radar entièrement hors de vue, à compléter avec set_cell et set_wall
+    pub fn new() -> Self {
+        RadarView {
+            horizontal_walls: [[Passage::Undefined; 3]; 4],
+            vertical_walls: [[Passage::Undefined; 4]; 3],
+            cells: [[RadarCell::Undefined; 3]; 3],
+        }
+    }
+
+    pub fn set_cell(&mut self, dx: i32, dy: i32, cell: RadarCell) -> &mut Self {
+        if let (Some(column), Some(row)) = (index(dx, 3), index(dy, 3)) {
+            self.cells[row][column] = cell;
+        }
+        self
+    }
+
+    // Modifier le passage sur le côté `direction` de la cellule `from`
+    pub fn set_wall(&mut self, from: Offset, direction: RelativeDirection, passage: Passage) -> &mut Self {
+        let (dx, dy) = from;
+        if index(dx, 3).is_none() || index(dy, 3).is_none() {
+            return self;
+        }
+        let (column, row) = ((dx + 1) as usize, (dy + 1) as usize);
+        match direction {
+            RelativeDirection::Front => self.horizontal_walls[row][column] = passage,
+            RelativeDirection::Back  => self.horizontal_walls[row + 1][column] = passage,
+            RelativeDirection::Left  => self.vertical_walls[row][column] = passage,
+            RelativeDirection::Right => self.vertical_walls[row][column + 1] = passage,
+        }
+        self
+    }
+
+    // Inverse de Display : 7 lignes de 7 symboles, les coins sont ignorés.
+    // Les symboles étant ambigus (X pour un adversaire sur la sortie par exemple),
+    // une cellule occupée est lue comme une cellule sans indice ni sortie.
+    pub fn from_ascii(text: &str) -> Result<Self, RadarError> {
+        let grid = parse_ascii_grid(text).map_err(RadarError::InvalidAscii)?;
+        if grid.len() != 7 || grid.iter().any(|row| row.len() != 7) {
+            return Err(RadarError::InvalidAscii("7 lignes de 7 symboles attendues".to_string()));
+        }
+        let symbol_error = |row: usize, column: usize, expected: &str| {
+            RadarError::InvalidAscii(format!(
+                "'{}' en ligne {}, colonne {} : {} attendu",
+                grid[row][column],
+                row + 1,
+                column + 1,
+                expected
+            ))
+        };
+
+        let mut radar = RadarView::new();
+        for (row, symbols) in grid.iter().enumerate() {
+            for (column, symbol) in symbols.iter().enumerate() {
+                match (row % 2, column % 2) {
+                    (0, 0) => {}
+                    (1, 1) => {
+                        let cell = symbol.to_radar_cell().ok_or_else(|| symbol_error(row, column, "une cellule"))?;
+                        radar.cells[row / 2][column / 2] = cell;
+                    }
+                    (0, _) => {
+                        let passage = symbol.to_passage().ok_or_else(|| symbol_error(row, column, "un mur"))?;
+                        radar.horizontal_walls[row / 2][column / 2] = passage;
+                    }
+                    _ => {
+                        let passage = symbol.to_passage().ok_or_else(|| symbol_error(row, column, "un mur"))?;
+                        radar.vertical_walls[row / 2][column / 2] = passage;
+                    }
+                }
+            }
+        }
+        Ok(radar)
+    }
+
+    // Radar tourné de `turns` quarts de tour dans le sens horaire
+    pub fn rotated(&self, turns: u8) -> RadarView {
+        let mut rotated = RadarView::new();
+        for (offset, cell) in self.cells() {
+            let (dx, dy) = rotate_offset(offset, turns);
+            rotated.set_cell(dx, dy, cell);
+        }
+        for wall in self.walls() {
+            rotated.set_wall(rotate_offset(wall.from, turns), rotate_relative(wall.direction, turns), wall.passage);
+        }
+        rotated
+    }
+
+    // Radar dans le repère absolu, le nord en haut : Front devient le nord, Right l'est, etc.
+    pub fn to_absolute(&self, heading: Heading) -> RadarView {
+        self.rotated(heading.quarter_turns())
+    }
+
+    // Inverse de to_absolute : le radar tel que le voit un joueur orienté selon `heading`
+    pub fn to_relative(&self, heading: Heading) -> RadarView {
+        self.rotated(4 - heading.quarter_turns())
+    }
+
+    // Inverse de from_bytes : les 11 octets tels que le serveur les envoie
+    pub fn to_bytes(&self) -> [u8; RADAR_BYTES] {
+        let mut bytes = [0u8; RADAR_BYTES];
+        let horizontal: Vec<Passage> = self.horizontal_walls.iter().flatten().copied().collect();
+        let vertical: Vec<Passage> = self.vertical_walls.iter().flatten().copied().collect();
+        bytes[HORIZONTAL_WALLS..VERTICAL_WALLS].copy_from_slice(&write_passages(&horizontal));
+        bytes[VERTICAL_WALLS..CELLS].copy_from_slice(&write_passages(&vertical));
+        for (index, cell) in self.cells.iter().flatten().enumerate() {
+            let nibble = cell.to_nibble();
+            bytes[CELLS + index / 2] |= if index % 2 == 0 { nibble << 4 } else { nibble };
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RadarError> {
+        if bytes.len() != RADAR_BYTES {
+            return Err(RadarError::InvalidLength { expected: RADAR_BYTES, actual: bytes.len() });
+        }
+
+        let horizontal = read_passages(&bytes[HORIZONTAL_WALLS..VERTICAL_WALLS]);
+        let vertical = read_passages(&bytes[VERTICAL_WALLS..CELLS]);
+        let mut radar = RadarView::new();
+        for (index, passage) in horizontal.into_iter().enumerate() {
+            radar.horizontal_walls[index / 3][index % 3] = passage;
+        }
+        for (index, passage) in vertical.into_iter().enumerate() {
+            radar.vertical_walls[index / 4][index % 4] = passage;
+        }
+
+        // Les cellules se lisent dans l'ordre des octets, 4 bits de poids fort en premier.
+        // Les 4 derniers bits sont du bourrage.
+        for index in 0..9 {
+            let byte = bytes[CELLS + index / 2];
+            let nibble = if index % 2 == 0 { byte >> 4 } else { byte & 0x0F };
+            radar.cells[index / 3][index % 3] = RadarCell::from_nibble(nibble);
+        }
+        Ok(radar)
+    }
+
+    // Cellule à la position (dx, dy), chaque coordonnée entre -1 et 1
+    pub fn cell(&self, dx: i32, dy: i32) -> RadarCell {
+        match (index(dx, 3), index(dy, 3)) {
+            (Some(column), Some(row)) => self.cells[row][column],
+            _ => RadarCell::Undefined,
+        }
+    }
+
+    // Passage sur le côté `direction` de la cellule `from`
+    pub fn wall(&self, from: Offset, direction: RelativeDirection) -> Passage {
+        let (dx, dy) = from;
+        if index(dx, 3).is_none() || index(dy, 3).is_none() {
+            return Passage::Undefined;
+        }
+        let (column, row) = ((dx + 1) as usize, (dy + 1) as usize);
+        match direction {
+            RelativeDirection::Front => self.horizontal_walls[row][column],
+            RelativeDirection::Back  => self.horizontal_walls[row + 1][column],
+            RelativeDirection::Left  => self.vertical_walls[row][column],
+            RelativeDirection::Right => self.vertical_walls[row][column + 1],
+        }
+    }
+
+    // Toutes les cellules, ligne par ligne depuis l'avant
+    pub fn cells(&self) -> impl Iterator<Item = (Offset, RadarCell)> + '_ {
+        (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| ((dx, dy), self.cell(dx, dy))))
+    }
+
+    pub fn visible_cells(&self) -> impl Iterator<Item = (Offset, RadarCell)> + '_ {
+        self.cells().filter(|(_, cell)| *cell != RadarCell::Undefined)
+    }
+
+    // Tous les murs, chacun une seule fois : avant/gauche de chaque cellule,
+    // plus l'arrière de la dernière ligne et la droite de la dernière colonne
+    pub fn walls(&self) -> impl Iterator<Item = RadarWall> + '_ {
+        let horizontal = (0..4).flat_map(move |row| {
+            (0..3).map(move |column| {
+                let (from, direction) = if row < 3 {
+                    ((column - 1, row - 1), RelativeDirection::Front)
+                } else {
+                    ((column - 1, 1), RelativeDirection::Back)
+                };
+                RadarWall { from, direction, passage: self.horizontal_walls[row as usize][column as usize] }
+            })
+        });
+        let vertical = (0..3).flat_map(move |row| {
+            (0..4).map(move |column| {
+                let (from, direction) = if column < 3 {
+                    ((column - 1, row - 1), RelativeDirection::Left)
+                } else {
+                    ((1, row - 1), RelativeDirection::Right)
+                };
+                RadarWall { from, direction, passage: self.vertical_walls[row as usize][column as usize] }
+            })
+        });
+        horizontal.chain(vertical)
+    }
+
+    pub fn visible_walls(&self) -> impl Iterator<Item = RadarWall> + '_ {
+        self.walls().filter(|wall| wall.passage != Passage::Undefined)
+    }
+
+    // Grille 7x7 : murs sur les lignes/colonnes paires, cellules sur les impaires
+    pub fn to_grid(&self) -> Vec<Vec<Cell>> {
+        let mut grid: Vec<Vec<Cell>> = vec![vec![Cell::Undefined; 7]; 7];
+        for (row, walls) in self.vertical_walls.iter().enumerate() {
+            for (column, passage) in walls.iter().enumerate() {
+                grid[2 * row + 1][2 * column] = Cell::from(*passage);
+            }
+        }
+        for (row, walls) in self.horizontal_walls.iter().enumerate() {
+            for (column, passage) in walls.iter().enumerate() {
+                grid[2 * row][2 * column + 1] = Cell::from(*passage);
+            }
+        }
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                grid[2 * row + 1][2 * column + 1] = Cell::from(*cell);
+            }
+        }
+        grid
+    }
+}
+
+impl fmt::Display for RadarView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.to_grid() {
+            let line: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+// Index dans un tableau de `size` éléments centré sur 0
+fn index(offset: i32, size: usize) -> Option<usize> {
+    let index = offset + (size as i32) / 2;
+    (0..size as i32).contains(&index).then_some(index as usize)
+}
+
+// 12 passages de 2 bits sur 3 octets, lus en little-endian, bits de poids fort en premier
+fn read_passages(bytes: &[u8]) -> [Passage; 12] {
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+    let mut passages = [Passage::Undefined; 12];
+    for (index, passage) in passages.iter_mut().enumerate() {
+        *passage = Passage::from_bits((value >> (22 - 2 * index)) as u8);
+    }
+    passages
+}
+
+fn write_passages(passages: &[Passage]) -> [u8; 3] {
+    let value = passages
+        .iter()
+        .enumerate()
+        .fold(0u32, |value, (index, passage)| value | (passage.to_bits() as u32) << (22 - 2 * index));
+    let bytes = value.to_le_bytes();
+    [bytes[0], bytes[1], bytes[2]]
+}
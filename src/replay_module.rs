@@ -0,0 +1,116 @@
+use std::{fmt, io, path::Path};
+
+use crate::communication_module::{read_recording, FrameDirection, RecordedFrame};
+use crate::player_module::{react, PlayerState, Reaction};
+use crate::protocol_module::{Action, ClientMessage, ServerMessage, SubscribePlayerResult};
+
+// Décision rejouée, comparée à l'action enregistrée pendant la partie
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedDecision {
+    pub message: ServerMessage,
+    pub decision: Reaction,
+    pub recorded: Option<Action>,
+}
+
+impl ReplayedDecision {
+    pub fn diverges(&self) -> bool {
+        match (&self.decision, &self.recorded) {
+            (Reaction::Act(action), Some(recorded)) => action != recorded,
+            (Reaction::Act(_), None) => true,
+            (_, Some(_)) => true,
+            (_, None) => false,
+        }
+    }
+}
+
+impl fmt::Display for ReplayedDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marker = if self.diverges() { "DIVERGENCE" } else { "ok" };
+        write!(f, "[{}] {:?} -> décision {:?}, enregistré {:?}", marker, self.message, self.decision, self.recorded)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    pub decisions: Vec<ReplayedDecision>,
+    pub skipped_frames: usize,
+}
+
+impl ReplayReport {
+    pub fn divergences(&self) -> impl Iterator<Item = &ReplayedDecision> {
+        self.decisions.iter().filter(|decision| decision.diverges())
+    }
+}
+
+// Rejouer un fichier d'enregistrement sans serveur
+pub fn replay_file(path: &Path) -> io::Result<ReplayReport> {
+    let frames = read_recording(path)?;
+    let player = frames
+        .first()
+        .map(|frame| frame.player.clone())
+        .unwrap_or_default();
+    Ok(replay(&frames, PlayerState::new(player, String::new())))
+}
+
+// Rejouer les trames reçues à travers la chaîne de décision actuelle (decoder, move_player...).
+// L'action envoyée juste après une trame reçue est considérée comme la décision enregistrée,
+// et c'est elle qui fait avancer la position du joueur pour la suite du rejeu.
+pub fn replay(frames: &[RecordedFrame], mut state: PlayerState) -> ReplayReport {
+    let mut report = ReplayReport::default();
+
+    for frame in frames {
+        match frame.direction {
+            FrameDirection::Inbound => match serde_json::from_str::<ServerMessage>(&frame.frame) {
+                // Une inscription acceptée ouvre une nouvelle session, comme après une reconnexion pendant la partie
+                Ok(ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)) => state.start_session(),
+                Ok(ServerMessage::SubscribePlayerResult(_)) | Ok(ServerMessage::RegisterTeamResult(_)) => {}
+                Ok(message) => {
+                    let decision = react(&mut state, message.clone());
+                    // Le suivi de position ne doit avancer qu'avec l'action réellement envoyée pendant la partie
+                    state.tracker.roll_back();
+                    report.decisions.push(ReplayedDecision { message, decision, recorded: None });
+                }
+                Err(err) => {
+                    eprintln!("Trame reçue illisible ignorée ({}) : {}", err, frame.frame);
+                    report.skipped_frames += 1;
+                }
+            },
+            FrameDirection::Outbound => match serde_json::from_str::<ClientMessage>(&frame.frame) {
+                Ok(ClientMessage::Action(action)) => {
+                    // Rattacher l'action à la dernière trame reçue qui n'en a pas encore
+                    match report.decisions.last_mut() {
+                        Some(last) if last.recorded.is_none() => {
+                            if let Action::MoveTo(direction) = action {
+                                state.tracker.start_move(direction);
+                            }
+                            last.recorded = Some(action);
+                        }
+                        _ => {
+                            eprintln!("Action enregistrée sans message serveur associé : {:?}", action);
+                            report.skipped_frames += 1;
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("Trame envoyée illisible ignorée ({}) : {}", err, frame.frame);
+                    report.skipped_frames += 1;
+                }
+            },
+        }
+    }
+
+    report
+}
+
+pub fn print_report(report: &ReplayReport) {
+    for decision in &report.decisions {
+        println!("{}", decision);
+    }
+    println!(
+        "{} décisions rejouées, {} divergences, {} trames ignorées",
+        report.decisions.len(),
+        report.divergences().count(),
+        report.skipped_frames
+    );
+}
@@ -0,0 +1,85 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::direction_module::Heading;
+use crate::map_module::{Position, WorldMap};
+use crate::radar_module::RadarView;
+
+// Carte partagée entre les threads des joueurs d'une même équipe
+pub type SharedTeamMap = Arc<Mutex<TeamMap>>;
+
+// Origine d'une cellule de la carte d'équipe : dernier joueur qui l'a vue, et quand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub player: String,
+    pub timestamp_ms: u64,
+}
+
+// Carte d'équipe dans le repère de la grille du serveur : chaque joueur a sa propre origine
+// (sa position de départ), il ne peut donc partager qu'une fois sa position dans la grille connue
+#[derive(Debug, Clone, Default)]
+pub struct TeamMap {
+    map: WorldMap,
+    provenance: HashMap<Position, Provenance>,
+}
+
+impl TeamMap {
+    pub fn new() -> Self {
+        TeamMap::default()
+    }
+
+    pub fn shared() -> SharedTeamMap {
+        Arc::new(Mutex::new(TeamMap::new()))
+    }
+
+    pub fn map(&self) -> &WorldMap {
+        &self.map
+    }
+
+    pub fn provenance(&self, position: Position) -> Option<&Provenance> {
+        self.provenance.get(&position)
+    }
+
+    // Ajouter un radar reçu par `player` à la position `position` de la grille
+    pub fn merge_radar(&mut self, player: &str, radar: &RadarView, position: Position, heading: Heading) {
+        self.map.merge(radar, position, heading);
+        let absolute = radar.to_absolute(heading);
+        let (x, y) = position;
+        let seen = absolute.visible_cells().map(|((dx, dy), _)| (x + dx, y + dy));
+        self.record(player, seen);
+    }
+
+    // Partager toute la carte d'un joueur, dont l'origine est en `offset` dans la grille.
+    // Les cellules déjà connues de l'équipe gardent leur provenance.
+    pub fn merge_map(&mut self, player: &str, map: &WorldMap, offset: Position) -> usize {
+        let discovered = self.map.merge_map(map, offset);
+        let (dx, dy) = offset;
+        let new_cells: Vec<Position> = map
+            .cells()
+            .map(|((x, y), _)| (x + dx, y + dy))
+            .filter(|position| !self.provenance.contains_key(position))
+            .collect();
+        self.record(player, new_cells);
+        discovered
+    }
+
+    fn record<I: IntoIterator<Item = Position>>(&mut self, player: &str, positions: I) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        for position in positions {
+            let provenance = Provenance { player: player.to_string(), timestamp_ms };
+            self.provenance.insert(position, provenance);
+        }
+    }
+}
+
+// Verrouiller la carte d'équipe, même si un autre joueur a paniqué en la tenant :
+// la carte reste utilisable, au pire avec un radar à moitié ajouté
+pub fn lock_team_map(team_map: &SharedTeamMap) -> MutexGuard<'_, TeamMap> {
+    team_map.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -1,105 +1,52 @@
 #![allow(unused)]
 use std::io::prelude::*;
 use std::net::TcpStream;
-use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
 
-use crate::communication_module::set_tcp_stream;
+use crate::communication_module::{set_tcp_stream, FrameCodec, SessionKind};
+use crate::config_module::Config;
+use crate::protocol_module::{ClientMessage, RegisterTeamResult, ServerMessage};
 
-#[derive(Serialize, Deserialize)]
-struct RegisterTeam {
-    name: String,
-}
-
-#[derive(Serialize, Deserialize)]
-enum Messages {
-    RegisterTeam { name: String },
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct RegisterTeamResponse {
-    RegisterTeamResult: RegisterTeamResult,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-enum RegisterTeamResult {
-    Ok(RegistrationSuccess),
-    Err(String),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct RegistrationSuccess {
-    expected_players: u32,
-    registration_token: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct AlreadyRegisteredTeam {
-    Err: String,
-}
-
-pub fn create_team( name: String) -> std::io::Result<Option<String>>  {
-    let mut stream = set_tcp_stream();
-    match stream {
+pub fn create_team( name: String, config: &Config) -> std::io::Result<Option<String>>  {
+    match set_tcp_stream(&config.server) {
         Ok(mut stream) => {
-            let message = Messages::RegisterTeam { name };
-            let serialized = serde_json::to_string(&message)?;
-
-            // Connexion au serveur
-            
-
-            // Envoyer la longueur du message (4 bytes)
-            let length = serialized.len() as u32;
-            stream.write_all(&length.to_le_bytes())?;
-
-            // Envoyer le message JSON
-            stream.write_all(serialized.as_bytes())?;
-
-            // Lire la longueur de la réponse (4 bytes)
-            let mut length_buffer = [0; 4];
-            stream.read_exact(&mut length_buffer)?;
-            let response_length = u32::from_le_bytes(length_buffer) as usize;
-
-            // Lire la réponse JSON complète
-            let mut response_buffer = vec![0; response_length];
-            stream.read_exact(&mut response_buffer)?;
-
-            // Convertir en String
-            let response_str = String::from_utf8_lossy(&response_buffer);
-            println!("Réponse brute: {}", response_str);
-
-            // Désérialiser la réponse
-            match serde_json::from_str::<RegisterTeamResponse>(&response_str) {
-                Ok(response) => {
-                    match response.RegisterTeamResult {
-                        RegisterTeamResult::Ok(success) => {
-                            println!("Inscription réussie !");
-                            println!("Nombre de joueurs attendus : {}", success.expected_players);
-                            println!("Token d'inscription : {}", success.registration_token);
-                            return Ok(Some(success.registration_token))
-                        }
-                        RegisterTeamResult::Err(error) => {
-                            println!("Erreur d'inscription : {}", error);
-                            return Err(io::Error::new(io::ErrorKind::Other, error))
-                        }
-                    }
+            let codec = FrameCodec::for_session(config.record_dir.as_deref(), SessionKind::Team, &name);
+            let message = ClientMessage::RegisterTeam { name };
+            codec.send_message(&mut stream, &message)?;
+
+            // Lire et désérialiser la réponse
+            let response = match codec.recv_message::<_, ServerMessage>(&mut stream) {
+                Ok(ServerMessage::RegisterTeamResult(response)) => response,
+                Ok(other) => {
+                    println!("Réponse inattendue du serveur : {:?}", other);
+                    return Ok(None);
                 }
-                Err(err) => {
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => {
                     println!("Erreur de parsing JSON : {}", err);
-                
+                    return Ok(None);
+                }
+                Err(err) => return Err(err),
+            };
+            println!("Réponse : {:?}", response);
+
+            match response {
+                RegisterTeamResult::Ok(success) => {
+                    println!("Inscription réussie !");
+                    println!("Nombre de joueurs attendus : {}", success.expected_players);
+                    println!("Token d'inscription : {}", success.registration_token);
+                    Ok(Some(success.registration_token))
+                }
+                RegisterTeamResult::Err(error) => {
+                    println!("Erreur d'inscription : {}", error);
+                    Err(io::Error::other(error.to_string()))
                 }
             }
-            Ok(None)
-            
-            
         }
         Err(err) => {
             println!("Erreur de serveur :{}", err);
             Ok(None)
         }
     }
-    // Sérialiser le message en JSON
-    
 }
 
 
@@ -143,14 +90,14 @@ pub fn ask_user_for_teams() -> Vec<String> {
     teams
 }
 
-pub fn register_team() -> std::io::Result<()> {
+pub fn register_team(config: &Config) -> std::io::Result<()> {
     // Demander à l'utilisateur les noms des équipes
     let teams = ask_user_for_teams();
 
     // Enregistrer chaque équipe
     for team_name in teams {
         println!("Enregistrement de l'équipe : {}", team_name);
-        if let Err(err) = create_team( team_name) {
+        if let Err(err) = create_team( team_name, config) {
             println!("Erreur lors de l'enregistrement de l'équipe : {}", err);
         }
     }
@@ -0,0 +1,102 @@
+use std::fmt;
+
+use crate::protocol_module::RelativeDirection;
+use crate::radar_module::{neighbour, Offset, Passage, RadarCell, RadarView, RadarWall};
+
+// Incohérence trouvée dans un radar décodé : le radar reste utilisable,
+// mais ces cas ne devraient pas arriver avec un décodage correct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadarWarning {
+    // Cellule codée avec une nature 11 autre que 1111
+    UnknownCellBits { at: Offset, bits: u8 },
+    // Passage codé 11
+    InvalidPassage { from: Offset, direction: RelativeDirection },
+    // Mur ou passage défini alors qu'aucune cellule autour n'est visible
+    WallBesideUndefined { from: Offset, direction: RelativeDirection },
+    // Passage ouvert entre une cellule visible et une cellule hors de vue
+    PassageIntoUndefined { from: Offset, direction: RelativeDirection },
+    // Cellule de coin visible alors qu'aucune des deux cellules qui y mènent ne l'est
+    DisconnectedCorner { at: Offset },
+    // La cellule du joueur est toujours visible
+    PlayerCellUndefined,
+}
+
+impl fmt::Display for RadarWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadarWarning::UnknownCellBits { at, bits } => {
+                write!(f, "cellule {:?} avec des bits inconnus {:04b}", at, bits)
+            }
+            RadarWarning::InvalidPassage { from, direction } => {
+                write!(f, "passage invalide (11) côté {} de la cellule {:?}", direction, from)
+            }
+            RadarWarning::WallBesideUndefined { from, direction } => {
+                write!(f, "mur côté {} de la cellule {:?} sans cellule visible autour", direction, from)
+            }
+            RadarWarning::PassageIntoUndefined { from, direction } => {
+                write!(f, "passage ouvert côté {} de la cellule {:?} vers une cellule hors de vue", direction, from)
+            }
+            RadarWarning::DisconnectedCorner { at } => {
+                write!(f, "coin {:?} visible sans cellule visible pour y mener", at)
+            }
+            RadarWarning::PlayerCellUndefined => write!(f, "cellule du joueur hors de vue"),
+        }
+    }
+}
+
+// Chercher les contradictions internes d'un radar
+pub fn validate_radar(radar: &RadarView) -> Vec<RadarWarning> {
+    let mut warnings = Vec::new();
+
+    if radar.cell(0, 0) == RadarCell::Undefined {
+        warnings.push(RadarWarning::PlayerCellUndefined);
+    }
+    for (at, cell) in radar.cells() {
+        if let RadarCell::Invalid(bits) = cell {
+            warnings.push(RadarWarning::UnknownCellBits { at, bits });
+        }
+    }
+    for wall in radar.walls() {
+        if let Some(warning) = check_wall(radar, &wall) {
+            warnings.push(warning);
+        }
+    }
+    for at in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+        let (dx, dy) = at;
+        if is_visible(radar.cell(dx, dy)) && !is_visible(radar.cell(dx, 0)) && !is_visible(radar.cell(0, dy)) {
+            warnings.push(RadarWarning::DisconnectedCorner { at });
+        }
+    }
+    warnings
+}
+
+fn check_wall(radar: &RadarView, wall: &RadarWall) -> Option<RadarWarning> {
+    let (from, direction) = (wall.from, wall.direction);
+    if wall.passage == Passage::Invalid {
+        return Some(RadarWarning::InvalidPassage { from, direction });
+    }
+    if wall.passage == Passage::Undefined {
+        return None;
+    }
+
+    // Cellules de part et d'autre du mur qui sont dans le radar
+    let (nx, ny) = neighbour(from, direction);
+    let inside = |(dx, dy): Offset| dx.abs() <= 1 && dy.abs() <= 1;
+    let sides: Vec<RadarCell> = [from, (nx, ny)]
+        .into_iter()
+        .filter(|offset| inside(*offset))
+        .map(|(dx, dy)| radar.cell(dx, dy))
+        .collect();
+
+    if !sides.iter().any(|cell| is_visible(*cell)) {
+        return Some(RadarWarning::WallBesideUndefined { from, direction });
+    }
+    if wall.passage == Passage::Open && sides.contains(&RadarCell::Undefined) {
+        return Some(RadarWarning::PassageIntoUndefined { from, direction });
+    }
+    None
+}
+
+fn is_visible(cell: RadarCell) -> bool {
+    cell != RadarCell::Undefined
+}
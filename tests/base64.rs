@@ -0,0 +1,41 @@
+use sauve_qui_peut::base64_module::{decode, encode, Base64Error, GAME, STANDARD};
+
+#[test]
+fn standard_alphabet_matches_rfc_vectors() {
+    let vectors = [
+        ("", ""),
+        ("f", "Zg"),
+        ("fo", "Zm8"),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg"),
+        ("fooba", "Zm9vYmE"),
+        ("foobar", "Zm9vYmFy"),
+    ];
+    for (plain, encoded) in vectors {
+        assert_eq!(encode(plain.as_bytes(), &STANDARD), encoded);
+        assert_eq!(decode(encoded, &STANDARD).unwrap(), plain.as_bytes());
+    }
+    assert_eq!(decode("Zm9vYg==", &STANDARD).unwrap(), b"foob");
+    assert_eq!(decode("Zm9vYmE=", &STANDARD).unwrap(), b"fooba");
+}
+
+#[test]
+fn game_alphabet_puts_lowercase_first() {
+    assert_eq!(encode(&[0x00, 0x10, 0x83], &GAME), "abcd");
+    assert_eq!(encode(&[0xFB, 0xFF], &GAME), "+/8");
+    assert_eq!(
+        decode("ieysGjGO8papd/a", &GAME).unwrap(),
+        [0x20, 0x46, 0x12, 0x80, 0x98, 0x28, 0xF0, 0xF0, 0x0F, 0x0F, 0xF0]
+    );
+}
+
+#[test]
+fn rejects_invalid_input() {
+    assert_eq!(decode("abcde", &GAME), Err(Base64Error::InvalidLength(5)));
+    assert_eq!(decode("ab*d", &GAME), Err(Base64Error::InvalidCharacter { character: '*', position: 2 }));
+    assert_eq!(decode("abé", &GAME), Err(Base64Error::InvalidCharacter { character: 'é', position: 2 }));
+    assert_eq!(decode("ab=d", &GAME), Err(Base64Error::InvalidCharacter { character: '=', position: 2 }));
+    // "Zh" et "Zm9" laissent des bits non nuls après le dernier octet
+    assert_eq!(decode("Zh", &STANDARD), Err(Base64Error::NonCanonicalTrailingBits { character: 'h' }));
+    assert_eq!(decode("Zm9", &STANDARD), Err(Base64Error::NonCanonicalTrailingBits { character: '9' }));
+}
@@ -0,0 +1,22 @@
+// Outils partagés par les tests d'intégration : chaque fichier de test n'en utilise qu'une partie
+#![allow(dead_code)]
+
+use sauve_qui_peut::direction_module::Heading;
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::{encoder, react, PlayerState, Reaction};
+use sauve_qui_peut::protocol_module::ServerMessage;
+
+// Joueur sans carte d'équipe ni token d'inscription
+pub fn player(name: &str) -> PlayerState {
+    PlayerState::new(name.to_string(), String::new())
+}
+
+// RadarView tel que le serveur l'enverrait à un joueur en (x, y) orienté selon `heading`
+pub fn radar_message(maze: &Maze, (x, y): (i32, i32), heading: Heading) -> ServerMessage {
+    ServerMessage::RadarView(encoder(&maze.radar_view_facing(x, y, heading)))
+}
+
+// Faire réagir le joueur au radar vu en (x, y)
+pub fn see(state: &mut PlayerState, maze: &Maze, position: (i32, i32), heading: Heading) -> Reaction {
+    react(state, radar_message(maze, position, heading))
+}
@@ -0,0 +1,77 @@
+use sauve_qui_peut::compass_module::{estimate_exit, CompassReading};
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::player_module::react;
+use sauve_qui_peut::protocol_module::{Hint, ServerMessage};
+
+mod common;
+use common::player;
+
+// Angle relatif que le serveur enverrait à un joueur en `position` orienté vers `facing`
+fn angle_towards(exit: (f64, f64), position: (i32, i32), facing: Direction) -> f32 {
+    let (dx, dy) = (exit.0 - position.0 as f64, exit.1 - position.1 as f64);
+    let bearing = dx.atan2(-dy).to_degrees() as f32;
+    (bearing - facing.degrees()).rem_euclid(360.0)
+}
+
+fn reading(exit: (f64, f64), position: (i32, i32), facing: Direction) -> CompassReading {
+    CompassReading { position, heading: Heading::new(facing), angle: angle_towards(exit, position, facing) }
+}
+
+#[test]
+fn bearing_combines_heading_and_angle() {
+    let reading = CompassReading { position: (0, 0), heading: Heading::new(Direction::West), angle: 135.0 };
+    assert_eq!(reading.bearing(), 45.0);
+    let reading = CompassReading { position: (0, 0), heading: Heading::new(Direction::South), angle: 270.0 };
+    assert_eq!(reading.bearing(), 90.0);
+}
+
+#[test]
+fn exact_bearings_intersect_at_the_exit() {
+    let exit = (5.0, -3.0);
+    let readings = [
+        reading(exit, (0, 0), Direction::North),
+        reading(exit, (2, 1), Direction::East),
+        reading(exit, (1, -4), Direction::South),
+    ];
+    let estimate = estimate_exit(&readings).unwrap();
+    assert!((estimate.x - exit.0).abs() < 1e-3 && (estimate.y - exit.1).abs() < 1e-3, "{}", estimate);
+    assert!(estimate.uncertainty < 1.0, "{}", estimate);
+    assert_eq!(estimate.cell(), (5, -3));
+    assert_eq!(estimate.readings, 3);
+}
+
+#[test]
+fn noisy_or_insufficient_bearings() {
+    let exit = (6.0, 2.0);
+    assert_eq!(estimate_exit(&[]), None);
+    assert_eq!(estimate_exit(&[reading(exit, (0, 0), Direction::North)]), None);
+    // Deux mesures sur la même droite ne donnent pas d'intersection
+    assert_eq!(estimate_exit(&[reading(exit, (0, 0), Direction::North), reading(exit, (3, 1), Direction::East)]), None);
+
+    // Des caps presque parallèles donnent une estimation peu fiable
+    let close = [reading(exit, (0, 0), Direction::North), reading(exit, (0, 1), Direction::North)];
+    let far = [reading(exit, (0, 0), Direction::North), reading(exit, (6, -4), Direction::North)];
+    let (close, far) = (estimate_exit(&close).unwrap(), estimate_exit(&far).unwrap());
+    assert!(close.uncertainty > 2.0 * far.uncertainty, "{} / {}", close, far);
+
+    // Des mesures incohérentes augmentent l'incertitude
+    let mut readings = vec![
+        reading(exit, (0, 0), Direction::North),
+        reading(exit, (0, 4), Direction::North),
+        reading(exit, (6, -4), Direction::North),
+    ];
+    let exact = estimate_exit(&readings).unwrap();
+    readings[1].angle += 15.0;
+    let noisy = estimate_exit(&readings).unwrap();
+    assert!(noisy.uncertainty > exact.uncertainty, "{} / {}", noisy, exact);
+    assert!((noisy.x - exit.0).abs() < 2.0 && (noisy.y - exit.1).abs() < 2.0, "{}", noisy);
+}
+
+#[test]
+fn compass_hints_are_recorded_with_tracked_position() {
+    let mut state = player("dreamy_lemon");
+    react(&mut state, ServerMessage::Hint(Hint::RelativeCompass { angle: 45.0 }));
+    assert_eq!(state.compass.len(), 1);
+    assert_eq!(state.compass[0].position, (0, 0));
+    assert_eq!(state.exit_estimate(), None);
+}
@@ -0,0 +1,131 @@
+use std::{env, fs, path::PathBuf, sync::{Mutex, MutexGuard}, time::Duration};
+
+use sauve_qui_peut::config_module::{Config, RetryPolicy, ServerConfig, CONFIG_ENV_VAR, DEFAULT_HOST, DEFAULT_PORT, SERVER_ENV_VAR};
+
+// Les variables d'environnement sont partagées par tous les tests du fichier
+static ENVIRONMENT: Mutex<()> = Mutex::new(());
+
+// Verrouiller l'environnement et partir sans SQP_SERVER ni SQP_CONFIG
+fn clean_environment() -> MutexGuard<'static, ()> {
+    let lock = ENVIRONMENT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    env::remove_var(SERVER_ENV_VAR);
+    env::remove_var(CONFIG_ENV_VAR);
+    lock
+}
+
+fn load(args: &[&str]) -> Result<Config, String> {
+    Config::load(args.iter().map(|arg| arg.to_string()))
+}
+
+fn config_file(name: &str, content: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("sauve_qui_peut_test_{}_{}.conf", std::process::id(), name));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn defaults_without_options() {
+    let _lock = clean_environment();
+    let config = load(&[]).unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(config.server.address(), format!("{}:{}", DEFAULT_HOST, DEFAULT_PORT));
+}
+
+#[test]
+fn parses_both_option_forms() {
+    let _lock = clean_environment();
+    let config = load(&["--port", "9000", "--connect-timeout-ms=250", "--record-dir", "/tmp/rec"]).unwrap();
+    assert_eq!(config.server.port, 9000);
+    assert_eq!(config.server.connect_timeout, Duration::from_millis(250));
+    assert_eq!(config.record_dir, Some(PathBuf::from("/tmp/rec")));
+
+    assert!(load(&["--port"]).unwrap_err().contains("Valeur manquante"));
+    assert!(load(&["port", "9000"]).unwrap_err().contains("Argument inattendu"));
+    assert!(load(&["--port=abc"]).unwrap_err().contains("Valeur invalide"));
+    assert!(load(&["--colour=red"]).unwrap_err().contains("Option inconnue"));
+    assert!(load(&["--connect-timeout-ms=0"]).unwrap_err().contains("délai de connexion"));
+}
+
+#[test]
+fn retry_delay_grows_exponentially_up_to_the_maximum() {
+    let policy = RetryPolicy {
+        max_attempts: 5,
+        initial_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(1000),
+    };
+    assert_eq!(policy.delay_for(0), Duration::from_millis(100));
+    assert_eq!(policy.delay_for(1), Duration::from_millis(200));
+    assert_eq!(policy.delay_for(3), Duration::from_millis(800));
+    assert_eq!(policy.delay_for(4), Duration::from_millis(1000));
+    // Sans dépassement de capacité, même après un très grand nombre de tentatives
+    assert_eq!(policy.delay_for(40), Duration::from_millis(1000));
+    assert_eq!(policy.delay_for(u32::MAX), Duration::from_millis(1000));
+
+    let unbounded = RetryPolicy { max_delay: Duration::MAX, ..policy };
+    assert_eq!(unbounded.delay_for(u32::MAX), Duration::from_millis(100).saturating_mul(u32::MAX));
+}
+
+#[test]
+fn parses_server_address() {
+    let _lock = clean_environment();
+    let config = load(&["--server", "game.example:4242"]).unwrap();
+    assert_eq!((config.server.host.as_str(), config.server.port), ("game.example", 4242));
+    // Sans port, seul l'hôte change
+    let config = load(&["--server=game.example"]).unwrap();
+    assert_eq!((config.server.host.as_str(), config.server.port), ("game.example", DEFAULT_PORT));
+    assert!(load(&["--server", ":4242"]).is_err());
+    assert!(load(&["--server", "game.example:port"]).is_err());
+}
+
+#[test]
+fn zero_timeout_disables_it() {
+    let _lock = clean_environment();
+    let config = load(&["--read-timeout-ms", "0", "--write-timeout-ms", "1500"]).unwrap();
+    assert_eq!(config.server.read_timeout, None);
+    assert_eq!(config.server.write_timeout, Some(Duration::from_millis(1500)));
+    assert_eq!(ServerConfig::default().read_timeout, Some(Duration::from_secs(30)));
+}
+
+#[test]
+fn precedence_is_defaults_file_environment_command_line() {
+    let _lock = clean_environment();
+    let file = config_file(
+        "precedence",
+        "# fichier de test\nserver = file.example:1111\nretry_attempts = 7\n\nread_timeout_ms = 100\n",
+    );
+    let path = file.to_str().unwrap();
+
+    // Le fichier remplace les valeurs par défaut
+    let config = load(&["--config", path]).unwrap();
+    assert_eq!(config.server.address(), "file.example:1111");
+    assert_eq!(config.server.retry.max_attempts, 7);
+    assert_eq!(config.server.connect_timeout, ServerConfig::default().connect_timeout);
+
+    // SQP_CONFIG désigne le fichier, SQP_SERVER le remplace
+    env::set_var(CONFIG_ENV_VAR, path);
+    assert_eq!(load(&[]).unwrap().server.address(), "file.example:1111");
+    env::set_var(SERVER_ENV_VAR, "env.example:2222");
+    let config = load(&[]).unwrap();
+    assert_eq!(config.server.address(), "env.example:2222");
+    assert_eq!(config.server.retry.max_attempts, 7);
+
+    // La ligne de commande l'emporte sur tout
+    let config = load(&["--port", "3333", "--retry-attempts=2"]).unwrap();
+    assert_eq!(config.server.address(), "env.example:3333");
+    assert_eq!(config.server.retry.max_attempts, 2);
+    assert_eq!(config.server.read_timeout, Some(Duration::from_millis(100)));
+
+    env::remove_var(SERVER_ENV_VAR);
+    env::remove_var(CONFIG_ENV_VAR);
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn reports_invalid_config_file_lines() {
+    let _lock = clean_environment();
+    let file = config_file("invalid", "port = 8000\nthis is not an option\n");
+    let err = load(&["--config", file.to_str().unwrap()]).unwrap_err();
+    assert!(err.contains(":2 :"), "{}", err);
+    fs::remove_file(&file).unwrap();
+    assert!(load(&["--config", "/nonexistent/sauve_qui_peut.conf"]).unwrap_err().contains("Impossible de lire"));
+}
@@ -0,0 +1,60 @@
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::decoder;
+use sauve_qui_peut::protocol_module::RelativeDirection;
+use sauve_qui_peut::radar_module::{CellNature, Occupant, Passage, RadarCell};
+
+const RELATIVE: [RelativeDirection; 4] =
+    [RelativeDirection::Front, RelativeDirection::Right, RelativeDirection::Back, RelativeDirection::Left];
+
+#[test]
+fn relative_and_absolute_moves_are_inverse() {
+    assert_eq!(Direction::East.turn(RelativeDirection::Right), Direction::South);
+    assert_eq!(Direction::North.turn(RelativeDirection::Left), Direction::West);
+    assert_eq!(Direction::West.turn(RelativeDirection::Back), Direction::East);
+    assert_eq!(Direction::North.relative_to(Direction::West), RelativeDirection::Right);
+    for facing in Direction::ALL {
+        let heading = Heading::new(facing);
+        for relative in RELATIVE {
+            assert_eq!(heading.to_relative(heading.to_absolute(relative)), relative);
+        }
+    }
+}
+
+#[test]
+fn heading_follows_moves() {
+    let mut heading = Heading::default();
+    assert_eq!(heading.record_move(RelativeDirection::Right), Direction::East);
+    assert_eq!(heading.record_move(RelativeDirection::Right), Direction::South);
+    assert_eq!(heading.record_move(RelativeDirection::Front), Direction::South);
+    assert_eq!(heading.record_move(RelativeDirection::Back), Direction::North);
+    assert_eq!(heading.offset_to_absolute((0, -1)), (0, -1));
+    heading.record_move(RelativeDirection::Left);
+    assert_eq!(heading.offset_to_absolute((0, -1)), Direction::West.offset());
+    assert_eq!(heading.offset_to_absolute((1, -1)), (-1, -1));
+    assert_eq!(heading.offset_to_relative((-1, -1)), (1, -1));
+}
+
+#[test]
+fn rotation_round_trips() {
+    let radar = decoder("ieysGjGO8papd/a").unwrap();
+    assert_eq!(radar.rotated(4), radar);
+    for facing in Direction::ALL {
+        let heading = Heading::new(facing);
+        assert_eq!(radar.to_absolute(heading).to_relative(heading), radar);
+    }
+}
+
+#[test]
+fn maze_radar_is_seen_from_player_heading() {
+    let hint = RadarCell::Visible { nature: CellNature::Hint, occupant: Occupant::Nobody };
+    let mut maze = Maze::new(3, 3);
+    maze.set_vertical_wall(2, 1, Passage::Wall).set_cell(1, 2, hint);
+
+    // Tourné vers l'est, le mur à l'est est devant et la cellule au sud est à droite
+    let radar = maze.radar_view_facing(1, 1, Heading::new(Direction::East));
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Front), Passage::Wall);
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Left), Passage::Open);
+    assert_eq!(radar.cell(1, 0), hint);
+    assert_eq!(radar.to_absolute(Heading::new(Direction::East)), maze.radar_view(1, 1));
+}
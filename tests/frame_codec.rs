@@ -0,0 +1,135 @@
+use std::io::{self, Cursor, Read};
+
+use sauve_qui_peut::communication_module::{is_connection_closed, is_timeout, FrameCodec, DEFAULT_MAX_FRAME_SIZE};
+use sauve_qui_peut::player_module::SessionOutcome;
+use sauve_qui_peut::protocol_module::{Action, ClientMessage, RelativeDirection, ServerMessage};
+
+// Lecteur qui ne rend que quelques octets à la fois, avec des interruptions, comme une socket lente
+struct ChunkedReader {
+    data: Cursor<Vec<u8>>,
+    chunk: usize,
+    interrupt: bool,
+}
+
+impl ChunkedReader {
+    fn new(data: Vec<u8>, chunk: usize) -> Self {
+        ChunkedReader { data: Cursor::new(data), chunk, interrupt: false }
+    }
+}
+
+impl Read for ChunkedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        }
+        let length = buf.len().min(self.chunk);
+        self.data.read(&mut buf[..length])
+    }
+}
+
+fn frame(length: u32, body: &[u8]) -> Vec<u8> {
+    let mut bytes = length.to_le_bytes().to_vec();
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+#[test]
+fn frames_and_messages_round_trip() {
+    let codec = FrameCodec::default();
+    let mut buffer = Vec::new();
+    codec.send_frame(&mut buffer, b"hello").unwrap();
+    codec.send_frame(&mut buffer, b"").unwrap();
+    let message = ClientMessage::Action(Action::MoveTo(RelativeDirection::Left));
+    codec.send_message(&mut buffer, &message).unwrap();
+    assert_eq!(&buffer[..9], &frame(5, b"hello")[..]);
+
+    let mut reader = Cursor::new(buffer);
+    assert_eq!(codec.recv_frame(&mut reader).unwrap(), b"hello");
+    assert_eq!(codec.recv_frame(&mut reader).unwrap(), b"");
+    assert_eq!(codec.recv_message::<_, ClientMessage>(&mut reader).unwrap(), message);
+}
+
+#[test]
+fn oversized_announced_length_is_rejected_before_reading_the_body() {
+    // Une longueur de 4 Go serait fatale si le tampon était alloué avant la vérification
+    let mut reader = Cursor::new(frame(u32::MAX, b"{}"));
+    let err = FrameCodec::default().recv_frame(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(reader.position(), 4, "le corps de la trame ne doit pas être lu");
+
+    let mut reader = Cursor::new(frame(DEFAULT_MAX_FRAME_SIZE as u32 + 1, b""));
+    assert_eq!(FrameCodec::default().recv_frame(&mut reader).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn frame_size_limit_is_configurable() {
+    let codec = FrameCodec::new(4);
+    let mut reader = Cursor::new(frame(4, b"abcd"));
+    assert_eq!(codec.recv_frame(&mut reader).unwrap(), b"abcd");
+    let mut reader = Cursor::new(frame(5, b"abcde"));
+    assert_eq!(codec.recv_frame(&mut reader).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    let mut buffer = Vec::new();
+    assert_eq!(codec.send_frame(&mut buffer, b"abcde").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert!(buffer.is_empty(), "rien ne doit être écrit pour une trame refusée");
+}
+
+#[test]
+fn reads_frames_delivered_in_small_chunks() {
+    let codec = FrameCodec::default();
+    let body = br#"{"Action":{"MoveTo":"Front"}}"#;
+    let mut data = frame(body.len() as u32, body);
+    data.extend(frame(2, b"{}"));
+    for chunk in [1, 2, 3, 5] {
+        let mut reader = ChunkedReader::new(data.clone(), chunk);
+        assert_eq!(codec.recv_frame(&mut reader).unwrap(), body, "morceaux de {}", chunk);
+        assert_eq!(codec.recv_frame(&mut reader).unwrap(), b"{}", "morceaux de {}", chunk);
+        assert!(is_connection_closed(&codec.recv_frame(&mut reader).unwrap_err()));
+    }
+}
+
+#[test]
+fn distinguishes_clean_close_from_truncated_frames() {
+    let codec = FrameCodec::default();
+
+    // Fin de flux entre deux trames : fermeture propre
+    let err = codec.recv_frame(&mut Cursor::new(Vec::new())).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert!(is_connection_closed(&err));
+
+    // Fin de flux au milieu de la longueur ou du contenu : trame tronquée
+    for data in [vec![5, 0], frame(5, b"ab")] {
+        let err = codec.recv_frame(&mut ChunkedReader::new(data.clone(), 1)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof, "{:?}", data);
+        assert!(!is_connection_closed(&err), "{:?}", data);
+        assert!(err.to_string().contains("tronquée"), "{}", err);
+    }
+}
+
+#[test]
+fn io_errors_map_to_session_outcomes() {
+    let codec = FrameCodec::default();
+    let closed = codec.recv_frame(&mut Cursor::new(Vec::new())).unwrap_err();
+    assert!(matches!(SessionOutcome::from(closed), SessionOutcome::Disconnected(_)));
+
+    let truncated = codec.recv_frame(&mut Cursor::new(frame(5, b"ab"))).unwrap_err();
+    assert!(matches!(SessionOutcome::from(truncated), SessionOutcome::ProtocolError(_)));
+
+    let invalid = codec.recv_message::<_, ServerMessage>(&mut Cursor::new(frame(2, b"{}"))).unwrap_err();
+    assert_eq!(invalid.kind(), io::ErrorKind::InvalidData);
+    assert!(matches!(SessionOutcome::from(invalid), SessionOutcome::ProtocolError(_)));
+
+    for kind in [io::ErrorKind::WouldBlock, io::ErrorKind::TimedOut] {
+        assert!(is_timeout(&io::Error::from(kind)));
+        assert_eq!(SessionOutcome::from(io::Error::from(kind)), SessionOutcome::TimedOut);
+    }
+    let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+    assert!(!is_timeout(&reset) && !is_connection_closed(&reset));
+    let outcome = SessionOutcome::from(reset);
+    assert!(matches!(outcome, SessionOutcome::Disconnected(_)));
+    assert!(outcome.is_recoverable());
+    assert!(SessionOutcome::TimedOut.is_recoverable());
+    assert!(!SessionOutcome::ProtocolError(String::new()).is_recoverable());
+    assert!(!SessionOutcome::Finished.is_recoverable());
+}
@@ -0,0 +1,87 @@
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::map_module::step;
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::{react, Reaction};
+use sauve_qui_peut::protocol_module::{Action, ActionError, RelativeDirection, ServerMessage};
+use sauve_qui_peut::radar_module::Passage;
+
+mod common;
+use common::{player, radar_message};
+
+#[test]
+fn tracker_follows_the_player_through_a_maze() {
+    let maze = Maze::from_ascii(
+        "W W W W W W W W W
+         W O O O W O O E W
+         W O W O W O W W W
+         W O W O O O O O W
+         W O W W W O W W W
+         W O O O O O O O W
+         W W W W W W W W W",
+    )
+    .unwrap();
+    let start = (0, 2);
+    let (mut position, mut heading) = (start, Heading::new(Direction::North));
+    let mut state = player("dreamy_lemon");
+
+    for _ in 0..40 {
+        let reaction = react(&mut state, radar_message(&maze, position, heading));
+        let tracked = state.tracker.position();
+        assert_eq!((tracked.0 + start.0, tracked.1 + start.1), position);
+        assert_eq!(state.tracker.heading(), heading);
+        match reaction {
+            Reaction::Act(Action::MoveTo(direction)) => {
+                let absolute = heading.to_absolute(direction);
+                assert_eq!(maze.passage(position.0, position.1, absolute), Passage::Open);
+                position = step(position, absolute);
+                heading = Heading::new(absolute);
+            }
+            Reaction::Finished => {
+                assert!(maze.cell(position.0, position.1).is_goal());
+                return;
+            }
+            other => panic!("réaction inattendue : {:?}", other),
+        }
+    }
+    panic!("sortie non atteinte");
+}
+
+#[test]
+fn refused_move_is_rolled_back() {
+    let maze = Maze::from_ascii("W W W W W W W\nW O O O O O W\nW W W W W W W").unwrap();
+    let mut state = player("dreamy_lemon");
+    let heading = Heading::new(Direction::North);
+
+    let Reaction::Act(Action::MoveTo(direction)) = react(&mut state, radar_message(&maze, (1, 0), heading)) else {
+        panic!("un déplacement était attendu");
+    };
+    assert_eq!(state.tracker.pending(), Some(direction));
+
+    // Le serveur refuse le déplacement : le joueur n'a pas bougé et le mur est noté sur la carte
+    react(&mut state, ServerMessage::ActionError(ActionError::CannotPassThroughWall));
+    assert_eq!(state.tracker.pending(), None);
+    assert_eq!((state.tracker.position(), state.tracker.heading()), ((0, 0), heading));
+    assert_eq!(state.map.passage((0, 0), heading.to_absolute(direction)), Passage::Wall);
+
+    react(&mut state, radar_message(&maze, (1, 0), heading));
+    assert_eq!(state.tracker.position(), (0, 0));
+    assert_eq!(state.tracker.pending(), Some(RelativeDirection::Right));
+}
+
+#[test]
+fn pending_move_is_dropped_when_a_new_session_starts() {
+    let maze = Maze::from_ascii("W W W W W W W\nW O O O O O W\nW W W W W W W").unwrap();
+    let mut state = player("dreamy_lemon");
+    let heading = Heading::new(Direction::North);
+
+    let Reaction::Act(Action::MoveTo(direction)) = react(&mut state, radar_message(&maze, (1, 0), heading)) else {
+        panic!("un déplacement était attendu");
+    };
+    assert_eq!(state.tracker.pending(), Some(direction));
+
+    // La connexion tombe avant le RadarView suivant : le déplacement n'est pas appliqué au premier radar reçu
+    state.start_session();
+    assert_eq!(state.tracker.pending(), None);
+    react(&mut state, radar_message(&maze, (1, 0), heading));
+    assert_eq!((state.tracker.position(), state.tracker.heading()), ((0, 0), heading));
+}
@@ -0,0 +1,79 @@
+// Tests aléatoires : chaque propriété est vérifiée sur quelques centaines d'entrées générées
+use quickcheck::{quickcheck, Arbitrary, Gen};
+
+use sauve_qui_peut::base64_module::{decode, encode, GAME, STANDARD};
+use sauve_qui_peut::player_module::{decoder, encoder};
+use sauve_qui_peut::radar_module::{Passage, RadarCell, RadarView};
+
+// RadarView quelconque, y compris avec des cellules et des murs invalides
+#[derive(Debug, Clone)]
+struct AnyRadarView(RadarView);
+
+impl Arbitrary for AnyRadarView {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let mut radar = RadarView::new();
+        let walls: Vec<_> = radar.walls().collect();
+        for wall in walls {
+            radar.set_wall(wall.from, wall.direction, Passage::from_bits(u8::arbitrary(g) & 0b11));
+        }
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                radar.set_cell(dx, dy, RadarCell::from_nibble(u8::arbitrary(g) & 0x0F));
+            }
+        }
+        AnyRadarView(radar)
+    }
+}
+
+// Chaîne composée uniquement de caractères base64, pour atteindre le décodage des octets
+#[derive(Debug, Clone)]
+struct Base64Like(String);
+
+impl Arbitrary for Base64Like {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let symbols: Vec<char> = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/=".chars().collect();
+        let length = usize::arbitrary(g) % 24;
+        Base64Like((0..length).map(|_| *g.choose(&symbols).unwrap()).collect())
+    }
+}
+
+quickcheck! {
+    fn base64_round_trip_standard(bytes: Vec<u8>) -> bool {
+        decode(&encode(&bytes, &STANDARD), &STANDARD) == Ok(bytes)
+    }
+
+    fn base64_round_trip_game(bytes: Vec<u8>) -> bool {
+        decode(&encode(&bytes, &GAME), &GAME) == Ok(bytes)
+    }
+
+    // Un seul encodage accepté par suite d'octets
+    fn base64_decoding_is_canonical(text: Base64Like) -> bool {
+        match decode(&text.0, &GAME) {
+            Ok(bytes) => encode(&bytes, &GAME) == text.0.trim_end_matches('='),
+            Err(_) => true,
+        }
+    }
+
+    fn radar_view_round_trip(radar: AnyRadarView) -> bool {
+        let encoded = encoder(&radar.0);
+        encoded.len() == 15 && decoder(&encoded) == Ok(radar.0)
+    }
+
+    fn radar_bytes_round_trip(radar: AnyRadarView) -> bool {
+        RadarView::from_bytes(&radar.0.to_bytes()) == Ok(radar.0)
+    }
+
+    // Une entrée quelconque donne une erreur ou un radar, jamais une panique
+    fn decoder_never_panics_on_garbage(text: String) -> bool {
+        let _ = decoder(&text);
+        true
+    }
+
+    fn decoder_never_panics_on_base64_garbage(text: Base64Like) -> bool {
+        decoder(&text.0).is_err() || text.0.trim_end_matches('=').len() == 15
+    }
+
+    fn from_bytes_never_panics(bytes: Vec<u8>) -> bool {
+        RadarView::from_bytes(&bytes).is_ok() == (bytes.len() == 11)
+    }
+}
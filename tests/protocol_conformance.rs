@@ -0,0 +1,152 @@
+// Tests de conformité du protocole : chaque message est comparé à un fichier JSON de référence
+// dans tests/fixtures/protocol. Les messages envoyés doivent être identiques octet pour octet,
+// les messages reçus doivent être désérialisés vers la valeur attendue.
+use std::{collections::BTreeSet, fs, path::Path};
+
+use sauve_qui_peut::protocol_module::*;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/protocol");
+
+fn fixture(kind: &str, name: &str) -> String {
+    let path = Path::new(FIXTURES).join(kind).join(format!("{}.json", name));
+    fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("Fixture {} illisible : {}", path.display(), err))
+        .trim_end()
+        .to_string()
+}
+
+fn client_messages() -> Vec<(&'static str, ClientMessage)> {
+    vec![
+        ("register_team", ClientMessage::RegisterTeam { name: "curious_broccoli".to_string() }),
+        (
+            "subscribe_player",
+            ClientMessage::SubscribePlayer {
+                name: "dreamy_lemon".to_string(),
+                registration_token: "3c2d1e0f".to_string(),
+            },
+        ),
+        ("action_move_to_front", ClientMessage::Action(Action::MoveTo(RelativeDirection::Front))),
+        ("action_move_to_right", ClientMessage::Action(Action::MoveTo(RelativeDirection::Right))),
+        ("action_move_to_back", ClientMessage::Action(Action::MoveTo(RelativeDirection::Back))),
+        ("action_move_to_left", ClientMessage::Action(Action::MoveTo(RelativeDirection::Left))),
+        (
+            "action_solve_challenge",
+            ClientMessage::Action(Action::SolveChallenge { answer: "42".to_string() }),
+        ),
+    ]
+}
+
+fn server_messages() -> Vec<(&'static str, ServerMessage)> {
+    vec![
+        (
+            "register_team_result_ok",
+            ServerMessage::RegisterTeamResult(RegisterTeamResult::Ok(RegistrationSuccess {
+                expected_players: 3,
+                registration_token: "3c2d1e0f".to_string(),
+            })),
+        ),
+        (
+            "register_team_result_already_registered",
+            ServerMessage::RegisterTeamResult(RegisterTeamResult::Err(RegistrationError::AlreadyRegistered)),
+        ),
+        (
+            "register_team_result_invalid_name",
+            ServerMessage::RegisterTeamResult(RegisterTeamResult::Err(RegistrationError::InvalidName)),
+        ),
+        ("subscribe_player_result_ok", ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)),
+        (
+            "subscribe_player_result_invalid_registration_token",
+            ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Err(
+                RegistrationError::InvalidRegistrationToken,
+            )),
+        ),
+        (
+            "subscribe_player_result_too_many_players",
+            ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Err(RegistrationError::TooManyPlayers)),
+        ),
+        ("radar_view", ServerMessage::RadarView("ieysGjGO8papd/a".to_string())),
+        ("hint_relative_compass", ServerMessage::Hint(Hint::RelativeCompass { angle: 192.5 })),
+        ("hint_grid_size", ServerMessage::Hint(Hint::GridSize { columns: 12, rows: 9 })),
+        ("hint_secret", ServerMessage::Hint(Hint::Secret(1234567890))),
+        ("hint_sos_helper", ServerMessage::Hint(Hint::SOSHelper)),
+        ("challenge_secret_sum_modulo", ServerMessage::Challenge(Challenge::SecretSumModulo(1000))),
+        ("challenge_sos", ServerMessage::Challenge(Challenge::Sos)),
+        (
+            "action_error_cannot_pass_through_wall",
+            ServerMessage::ActionError(ActionError::CannotPassThroughWall),
+        ),
+        (
+            "action_error_cannot_pass_through_opponent",
+            ServerMessage::ActionError(ActionError::CannotPassThroughOpponent),
+        ),
+        (
+            "action_error_no_running_challenge",
+            ServerMessage::ActionError(ActionError::NoRunningChallenge),
+        ),
+        (
+            "action_error_solve_challenge_first",
+            ServerMessage::ActionError(ActionError::SolveChallengeFirst),
+        ),
+        (
+            "action_error_invalid_challenge_solution",
+            ServerMessage::ActionError(ActionError::InvalidChallengeSolution),
+        ),
+    ]
+}
+
+fn fixture_names(kind: &str) -> BTreeSet<String> {
+    fs::read_dir(Path::new(FIXTURES).join(kind))
+        .expect("Dossier de fixtures introuvable")
+        .map(|entry| entry.expect("Entrée de dossier illisible").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+        .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn client_messages_serialize_exactly_like_fixtures() {
+    for (name, message) in client_messages() {
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert_eq!(serialized, fixture("client", name), "sérialisation de {}", name);
+    }
+}
+
+#[test]
+fn client_fixtures_deserialize_back() {
+    for (name, message) in client_messages() {
+        let parsed: ClientMessage = serde_json::from_str(&fixture("client", name))
+            .unwrap_or_else(|err| panic!("désérialisation de {} : {}", name, err));
+        assert_eq!(parsed, message, "désérialisation de {}", name);
+    }
+}
+
+#[test]
+fn server_fixtures_deserialize_to_expected_messages() {
+    for (name, message) in server_messages() {
+        let parsed: ServerMessage = serde_json::from_str(&fixture("server", name))
+            .unwrap_or_else(|err| panic!("désérialisation de {} : {}", name, err));
+        assert_eq!(parsed, message, "désérialisation de {}", name);
+    }
+}
+
+#[test]
+fn server_messages_serialize_like_fixtures() {
+    for (name, message) in server_messages() {
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert_eq!(serialized, fixture("server", name), "sérialisation de {}", name);
+    }
+}
+
+#[test]
+fn every_fixture_is_covered() {
+    let client: BTreeSet<String> = client_messages().iter().map(|(name, _)| name.to_string()).collect();
+    let server: BTreeSet<String> = server_messages().iter().map(|(name, _)| name.to_string()).collect();
+    assert_eq!(fixture_names("client"), client);
+    assert_eq!(fixture_names("server"), server);
+}
+
+#[test]
+fn unknown_variants_are_rejected() {
+    assert!(serde_json::from_str::<ServerMessage>(r#"{"ActionError":"CannotFly"}"#).is_err());
+    assert!(serde_json::from_str::<ServerMessage>(r#"{"Teleport":{}}"#).is_err());
+}
@@ -0,0 +1,85 @@
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::{decoder, encoder, move_player};
+use sauve_qui_peut::protocol_module::RelativeDirection;
+use sauve_qui_peut::radar_module::{CellNature, Occupant, Passage, RadarCell, RadarView};
+
+#[test]
+fn encoder_is_inverse_of_decoder() {
+    let encoded = "ieysGjGO8papd/a";
+    assert_eq!(encoder(&decoder(encoded).unwrap()), encoded);
+    assert_eq!(encoder(&RadarView::new()).len(), 15);
+}
+
+#[test]
+fn built_radar_view_survives_round_trip() {
+    let goal = RadarCell::Visible { nature: CellNature::Goal, occupant: Occupant::Nobody };
+    let mut radar = RadarView::new();
+    radar
+        .set_cell(0, 0, RadarCell::Visible { nature: CellNature::Nothing, occupant: Occupant::Nobody })
+        .set_cell(0, -1, goal)
+        .set_wall((0, 0), RelativeDirection::Front, Passage::Open)
+        .set_wall((0, 0), RelativeDirection::Right, Passage::Wall);
+    let decoded = decoder(&encoder(&radar)).unwrap();
+    assert_eq!(decoded, radar);
+    assert_eq!(move_player(&decoded, None), Some(RelativeDirection::Front));
+}
+
+#[test]
+fn maze_radar_view_sees_borders() {
+    let mut maze = Maze::new(3, 2);
+    maze.set_vertical_wall(1, 0, Passage::Wall);
+    let radar = maze.radar_view(0, 0);
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Front), Passage::Wall);
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Left), Passage::Wall);
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Right), Passage::Wall);
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Back), Passage::Open);
+    assert_eq!(radar.cell(-1, 0), RadarCell::Undefined);
+    assert_eq!(radar.cell(0, -1), RadarCell::Undefined);
+    assert_eq!(radar.wall((-1, -1), RelativeDirection::Right), Passage::Undefined);
+    assert_eq!(move_player(&radar, None), Some(RelativeDirection::Back));
+}
+
+#[test]
+fn maze_literal_gives_expected_radar() {
+    let maze = Maze::from_ascii(
+        "W W W W W W W
+         W O O O W E W
+         W O W W W O W
+         W O O O O O W
+         W W W W W W W",
+    )
+    .unwrap();
+    assert_eq!((maze.width(), maze.height()), (3, 2));
+    assert_eq!(Maze::from_ascii(&maze.to_string()), Ok(maze.clone()));
+    // La cellule à l'ouest est derrière un mur, celle au sud-ouest est vue par la cellule du sud
+    let expected = RadarView::from_ascii(
+        "U U U U U U U
+         U U U U U U U
+         U U U W U U U
+         U U W E W U U
+         U U U O U U U
+         O O O O W U U
+         U W U W U U U",
+    )
+    .unwrap();
+    assert_eq!(maze.radar_view(2, 0), expected);
+    assert_eq!(move_player(&maze.radar_view(2, 1), None), Some(RelativeDirection::Front));
+    assert!(Maze::from_ascii("W W\nW O").is_err());
+}
+
+#[test]
+fn maze_radar_hides_cells_behind_walls_like_the_server() {
+    let maze = Maze::from_ascii(
+        "W W W W W W W
+         W O O O O O W
+         W O W O W O W
+         W O W O W O W
+         W W W O W O W
+         W O O O W O W
+         W O W W W O W
+         W O O O O O W
+         W W W W W W W",
+    )
+    .unwrap();
+    assert_eq!(encoder(&maze.radar_view(1, 2)), "ieysGjGO8papd/a");
+}
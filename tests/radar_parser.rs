@@ -0,0 +1,120 @@
+use sauve_qui_peut::player_module::decoder;
+use sauve_qui_peut::protocol_module::RelativeDirection;
+use sauve_qui_peut::radar_module::{Cell, CellNature, Occupant, Passage, RadarCell, RadarError, RadarView, RADAR_BYTES};
+
+fn grid_to_strings(encoded: &str) -> Vec<String> {
+    decoder(encoded)
+        .expect("RadarView valide")
+        .to_string()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn decodes_known_radar_view() {
+    assert_eq!(
+        grid_to_strings("ieysGjGO8papd/a"),
+        vec![
+            "U U U O U U U",
+            "U U W O W U U",
+            "U W U O U U U",
+            "W O O O W U U",
+            "U O U W U U U",
+            "W O U U U U U",
+            "U W U U U U U",
+        ]
+    );
+}
+
+#[test]
+fn rejects_wrong_length() {
+    assert_eq!(
+        RadarView::from_bytes(&[0; 6]),
+        Err(RadarError::InvalidLength { expected: RADAR_BYTES, actual: 6 })
+    );
+    assert_eq!(
+        RadarView::from_bytes(&[0; 12]),
+        Err(RadarError::InvalidLength { expected: RADAR_BYTES, actual: 12 })
+    );
+    assert!(matches!(decoder("AAAA"), Err(RadarError::InvalidLength { actual: 3, .. })));
+}
+
+#[test]
+fn rejects_invalid_base64() {
+    assert!(matches!(decoder("ieysGjGO8pa*d/a"), Err(RadarError::InvalidBase64(_))));
+}
+
+#[test]
+fn decodes_cell_nature_and_occupant() {
+    let visible = |nature, occupant| RadarCell::Visible { nature, occupant };
+    assert_eq!(RadarCell::from_nibble(0b0000), visible(CellNature::Nothing, Occupant::Nobody));
+    assert_eq!(RadarCell::from_nibble(0b0001), visible(CellNature::Nothing, Occupant::Ally));
+    assert_eq!(RadarCell::from_nibble(0b0110), visible(CellNature::Hint, Occupant::Enemy));
+    assert_eq!(RadarCell::from_nibble(0b1011), visible(CellNature::Goal, Occupant::Monster));
+    assert_eq!(RadarCell::from_nibble(0b1111), RadarCell::Undefined);
+    assert_eq!(RadarCell::from_nibble(0b1101), RadarCell::Invalid(0b1101));
+}
+
+#[test]
+fn accessors_match_grid_positions() {
+    let radar = decoder("ieysGjGO8papd/a").unwrap();
+    let grid = radar.to_grid();
+    for ((dx, dy), cell) in radar.cells() {
+        let (row, column) = ((2 * dy + 3) as usize, (2 * dx + 3) as usize);
+        assert_eq!(grid[row][column], Cell::from(cell), "cellule ({}, {})", dx, dy);
+    }
+    assert_eq!(radar.walls().count(), 24);
+    for wall in radar.walls() {
+        let (dx, dy) = wall.from;
+        let (row, column) = match wall.direction {
+            RelativeDirection::Front => (2 * dy + 2, 2 * dx + 3),
+            RelativeDirection::Back => (2 * dy + 4, 2 * dx + 3),
+            RelativeDirection::Left => (2 * dy + 3, 2 * dx + 2),
+            RelativeDirection::Right => (2 * dy + 3, 2 * dx + 4),
+        };
+        assert_eq!(grid[row as usize][column as usize], Cell::from(wall.passage), "{:?}", wall);
+        assert_eq!(radar.wall(wall.from, wall.direction), wall.passage);
+    }
+    assert_eq!(radar.cell(2, 0), RadarCell::Undefined);
+}
+
+#[test]
+fn parses_ascii_radar() {
+    let radar = decoder("ieysGjGO8papd/a").unwrap();
+    assert_eq!(RadarView::from_ascii(&radar.to_string()), Ok(radar.clone()));
+    let compact = "
+        UUUOUUU
+        UUWOWUU
+        UWUOUUU
+        WOOOWUU
+        UOUWUUU
+        WOUUUUU
+        UWUUUUU
+    ";
+    assert_eq!(RadarView::from_ascii(compact), Ok(radar));
+
+    let radar = RadarView::from_ascii(
+        "U U U U U U U
+         U E O H W M U
+         U U U W U U U
+         U ?1101 U O U A U
+         U U U U U U U
+         U U U X U U U
+         U U U U U U U",
+    )
+    .unwrap();
+    assert!(radar.cell(-1, -1).is_goal());
+    assert_eq!(radar.cell(1, -1), RadarCell::Visible { nature: CellNature::Nothing, occupant: Occupant::Monster });
+    assert_eq!(radar.cell(-1, 0), RadarCell::Invalid(0b1101));
+    assert_eq!(radar.wall((0, 0), RelativeDirection::Front), Passage::Wall);
+    assert_eq!(radar.wall((1, -1), RelativeDirection::Left), Passage::Wall);
+}
+
+#[test]
+fn rejects_malformed_ascii_radar() {
+    assert!(matches!(RadarView::from_ascii("UUU\nUOU\nUUU"), Err(RadarError::InvalidAscii(_))));
+    let wall_in_cell = "UUUUUUU\nUUUUUUU\nUUUUUUU\nUUUWUUU\nUUUUUUU\nUUUUUUU\nUUUUUUU";
+    assert!(matches!(RadarView::from_ascii(wall_in_cell), Err(RadarError::InvalidAscii(_))));
+    assert!(matches!(RadarView::from_ascii(&wall_in_cell.replace('W', "Z")), Err(RadarError::InvalidAscii(_))));
+}
@@ -0,0 +1,51 @@
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::decoder;
+use sauve_qui_peut::protocol_module::RelativeDirection;
+use sauve_qui_peut::radar_module::RadarView;
+use sauve_qui_peut::validation_module::{validate_radar, RadarWarning};
+
+#[test]
+fn server_and_maze_radars_are_consistent() {
+    assert_eq!(validate_radar(&decoder("ieysGjGO8papd/a").unwrap()), vec![]);
+    let maze = Maze::from_ascii(
+        "W W W W W W W
+         W O O O W E W
+         W O W W W O W
+         W O O O O O W
+         W W W W W W W",
+    )
+    .unwrap();
+    for y in 0..2 {
+        for x in 0..3 {
+            assert_eq!(validate_radar(&maze.radar_view(x, y)), vec![], "position ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn reports_contradictions() {
+    let radar = RadarView::from_ascii(
+        "U W U U U U U
+         U U U U U U U
+         U U U O U U U
+         U ?1101 O U O M U
+         U U U U U U U
+         U U U U U U U
+         U U U U U U U",
+    )
+    .unwrap();
+    assert_eq!(
+        validate_radar(&radar),
+        vec![
+            RadarWarning::PlayerCellUndefined,
+            RadarWarning::UnknownCellBits { at: (-1, 0), bits: 0b1101 },
+            RadarWarning::WallBesideUndefined { from: (-1, -1), direction: RelativeDirection::Front },
+            RadarWarning::WallBesideUndefined { from: (0, 0), direction: RelativeDirection::Front },
+            RadarWarning::PassageIntoUndefined { from: (0, 0), direction: RelativeDirection::Left },
+            RadarWarning::PassageIntoUndefined { from: (1, 0), direction: RelativeDirection::Left },
+        ]
+    );
+
+    let corner = RadarView::from_ascii("UUUUUUU\nUOUUUUU\nUUUUUUU\nUUUOUUU\nUUUUUUU\nUUUUUUU\nUUUUUUU").unwrap();
+    assert_eq!(validate_radar(&corner), vec![RadarWarning::DisconnectedCorner { at: (-1, -1) }]);
+}
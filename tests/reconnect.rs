@@ -0,0 +1,85 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use sauve_qui_peut::communication_module::FrameCodec;
+use sauve_qui_peut::config_module::Config;
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::{play_with_reconnect, SessionOutcome};
+use sauve_qui_peut::protocol_module::{ClientMessage, RegistrationError, ServerMessage, SubscribePlayerResult};
+
+mod common;
+use common::{player, radar_message};
+
+// Serveur local qui répond à chaque inscription par `subscribed`, envoie un radar aux `with_radar`
+// premières connexions puis coupe. Renvoie la configuration pour s'y connecter et le nombre de connexions.
+fn flaky_server(subscribed: SubscribePlayerResult, with_radar: usize) -> (Config, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut config = Config::default();
+    config.server.host = "127.0.0.1".to_string();
+    config.server.port = listener.local_addr().unwrap().port();
+    config.server.reconnect.max_attempts = 2;
+    config.server.reconnect.initial_delay = Duration::from_millis(1);
+    config.server.reconnect.max_delay = Duration::from_millis(1);
+
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&connections);
+    thread::spawn(move || {
+        let codec = FrameCodec::default();
+        let maze = Maze::new(3, 3);
+        for mut stream in listener.incoming().map_while(Result::ok) {
+            let index = counter.fetch_add(1, Ordering::SeqCst);
+            let Ok(ClientMessage::SubscribePlayer { .. }) = codec.recv_message(&mut stream) else {
+                continue;
+            };
+            let reply = ServerMessage::SubscribePlayerResult(subscribed.clone());
+            if codec.send_message(&mut stream, &reply).is_err() || index >= with_radar {
+                continue;
+            }
+            let radar = radar_message(&maze, (1, 1), Heading::new(Direction::North));
+            if codec.send_message(&mut stream, &radar).is_ok() {
+                // Attendre l'action du joueur avant de couper
+                let _ = codec.recv_message::<_, ClientMessage>(&mut stream);
+            }
+        }
+    });
+    (config, connections)
+}
+
+fn connect(config: &Config) -> TcpStream {
+    TcpStream::connect(config.server.address()).unwrap()
+}
+
+#[test]
+fn subscriptions_without_radar_do_not_reset_the_reconnection_count() {
+    let (config, connections) = flaky_server(SubscribePlayerResult::Ok, 2);
+    let mut state = player("dreamy_lemon");
+
+    let outcome = play_with_reconnect(connect(&config), &mut state, &config);
+
+    // Les deux premières sessions progressent, puis deux reprises sans radar épuisent les tentatives
+    assert!(matches!(outcome, SessionOutcome::Disconnected(_)), "{}", outcome);
+    assert_eq!(connections.load(Ordering::SeqCst), 4);
+    // La carte vue pendant les premières sessions est conservée, le déplacement interrompu est abandonné
+    assert_eq!(state.map.cells().count(), 9);
+    assert_eq!(state.tracker.pending(), None);
+}
+
+#[test]
+fn rejected_subscription_is_final() {
+    let (config, connections) = flaky_server(SubscribePlayerResult::Err(RegistrationError::TooManyPlayers), 0);
+    let mut state = player("dreamy_lemon");
+
+    let outcome = play_with_reconnect(connect(&config), &mut state, &config);
+
+    assert_eq!(outcome, SessionOutcome::Rejected(RegistrationError::TooManyPlayers));
+    assert!(!outcome.is_recoverable());
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,139 @@
+use sauve_qui_peut::communication_module::{FrameDirection, RecordedFrame};
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::Reaction;
+use sauve_qui_peut::protocol_module::{
+    Action, ClientMessage, Hint, RelativeDirection, ServerMessage, SubscribePlayerResult,
+};
+use sauve_qui_peut::replay_module::replay;
+
+mod common;
+use common::{player, radar_message};
+
+fn inbound(message: &ServerMessage) -> RecordedFrame {
+    recorded(FrameDirection::Inbound, serde_json::to_string(message).unwrap())
+}
+
+fn outbound(action: Action) -> RecordedFrame {
+    recorded(FrameDirection::Outbound, serde_json::to_string(&ClientMessage::Action(action)).unwrap())
+}
+
+fn recorded(direction: FrameDirection, frame: String) -> RecordedFrame {
+    RecordedFrame { direction, player: "dreamy_lemon".to_string(), timestamp_ms: 0, frame }
+}
+
+#[test]
+fn replay_flags_recorded_actions_that_disagree_with_the_decision() {
+    let maze = Maze::from_ascii("W W W W W W W\nW O O O O O W\nW W W W W W W").unwrap();
+    let frames = vec![
+        inbound(&ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)),
+        inbound(&ServerMessage::Hint(Hint::GridSize { columns: 5, rows: 1 })),
+        // Au bout ouest du couloir, seule la droite est ouverte : même décision qu'à l'enregistrement
+        inbound(&radar_message(&maze, (0, 0), Heading::new(Direction::North))),
+        outbound(Action::MoveTo(RelativeDirection::Right)),
+        // Dans le couloir, le joueur avance alors que l'enregistrement fait demi-tour
+        inbound(&radar_message(&maze, (1, 0), Heading::new(Direction::East))),
+        outbound(Action::MoveTo(RelativeDirection::Back)),
+        recorded(FrameDirection::Inbound, "{\"RadarView\":".to_string()),
+        outbound(Action::MoveTo(RelativeDirection::Front)),
+    ];
+
+    let report = replay(&frames, player("dreamy_lemon"));
+
+    // L'inscription est ignorée, la trame illisible et l'action qui suit la trame déjà appariée sont sautées
+    assert_eq!(report.decisions.len(), 3);
+    assert_eq!(report.skipped_frames, 2);
+
+    let [hint, first, second] = &report.decisions[..] else { unreachable!() };
+    assert_eq!(hint.decision, Reaction::Wait);
+    assert_eq!(hint.recorded, None);
+    assert!(!hint.diverges());
+
+    assert_eq!(first.decision, Reaction::Act(Action::MoveTo(RelativeDirection::Right)));
+    assert!(!first.diverges());
+
+    assert_eq!(second.decision, Reaction::Act(Action::MoveTo(RelativeDirection::Front)));
+    assert_eq!(second.recorded, Some(Action::MoveTo(RelativeDirection::Back)));
+    assert!(second.diverges());
+
+    let divergences: Vec<_> = report.divergences().collect();
+    assert_eq!(divergences, vec![second]);
+}
+
+#[test]
+fn decision_without_recorded_action_diverges() {
+    let maze = Maze::from_ascii("W W W W W\nW O O O W\nW W W W W").unwrap();
+    let frames = vec![inbound(&radar_message(&maze, (0, 0), Heading::new(Direction::North)))];
+
+    let report = replay(&frames, player("dreamy_lemon"));
+
+    assert_eq!(report.decisions.len(), 1);
+    assert_eq!(report.decisions[0].recorded, None);
+    assert!(report.decisions[0].diverges());
+}
+
+#[test]
+fn replay_follows_the_recorded_moves() {
+    // Couloir de trois cellules avec un embranchement vers le sud au milieu
+    let maze = Maze::from_ascii(
+        "W W W W W W W
+         W O O O O O W
+         W W W O W W W
+         W O W O W O W
+         W W W W W W W",
+    )
+    .unwrap();
+    let frames = vec![
+        // La sortie est à l'est : le joueur devrait tourner à droite, mais l'enregistrement part à gauche
+        inbound(&ServerMessage::Hint(Hint::RelativeCompass { angle: 90.0 })),
+        inbound(&radar_message(&maze, (1, 0), Heading::new(Direction::North))),
+        outbound(Action::MoveTo(RelativeDirection::Left)),
+        inbound(&radar_message(&maze, (0, 0), Heading::new(Direction::West))),
+        outbound(Action::MoveTo(RelativeDirection::Back)),
+        // De retour à l'embranchement, tourné vers l'est : la sortie est droit devant
+        inbound(&radar_message(&maze, (1, 0), Heading::new(Direction::East))),
+        outbound(Action::MoveTo(RelativeDirection::Front)),
+    ];
+
+    let report = replay(&frames, player("dreamy_lemon"));
+
+    let decisions: Vec<_> = report.decisions.iter().map(|decision| decision.decision.clone()).collect();
+    assert_eq!(
+        decisions,
+        vec![
+            Reaction::Wait,
+            Reaction::Act(Action::MoveTo(RelativeDirection::Right)),
+            Reaction::Act(Action::MoveTo(RelativeDirection::Back)),
+            Reaction::Act(Action::MoveTo(RelativeDirection::Front)),
+        ]
+    );
+    assert_eq!(report.divergences().count(), 1);
+}
+
+#[test]
+fn move_recorded_before_a_reconnection_is_not_applied() {
+    let maze = Maze::new(3, 3);
+    let frames = vec![
+        inbound(&ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)),
+        // La sortie est à droite du joueur, tourné vers le nord
+        inbound(&ServerMessage::Hint(Hint::RelativeCompass { angle: 90.0 })),
+        inbound(&radar_message(&maze, (1, 1), Heading::new(Direction::North))),
+        outbound(Action::MoveTo(RelativeDirection::Right)),
+        // La connexion tombe avant le radar suivant : le déplacement n'a peut-être jamais eu lieu
+        inbound(&ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)),
+        inbound(&radar_message(&maze, (1, 1), Heading::new(Direction::North))),
+    ];
+
+    let report = replay(&frames, player("dreamy_lemon"));
+
+    // Toujours tourné vers le nord : la sortie est encore à droite, et non droit devant
+    let decisions: Vec<_> = report.decisions.iter().map(|decision| decision.decision.clone()).collect();
+    assert_eq!(
+        decisions,
+        vec![
+            Reaction::Wait,
+            Reaction::Act(Action::MoveTo(RelativeDirection::Right)),
+            Reaction::Act(Action::MoveTo(RelativeDirection::Right)),
+        ]
+    );
+}
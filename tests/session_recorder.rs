@@ -0,0 +1,78 @@
+use std::{env, fs, io::Cursor, path::PathBuf};
+
+use sauve_qui_peut::communication_module::{read_recording, FrameCodec, FrameDirection, SessionKind, SessionRecorder};
+use sauve_qui_peut::protocol_module::{Action, ClientMessage, RelativeDirection, ServerMessage, SubscribePlayerResult};
+
+// Dossier d'enregistrement propre à chaque test, vidé au départ
+fn record_directory(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("sauve_qui_peut_test_{}_{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&directory);
+    directory
+}
+
+#[test]
+fn recorded_frames_round_trip() {
+    let directory = record_directory("round_trip");
+    let recorder = SessionRecorder::create(&directory, SessionKind::Player, "dreamy_lemon").unwrap();
+    let path = recorder.path().to_path_buf();
+    let codec = FrameCodec::default().with_recorder(recorder);
+
+    let sent = ClientMessage::Action(Action::MoveTo(RelativeDirection::Left));
+    let mut buffer = Vec::new();
+    codec.send_message(&mut buffer, &sent).unwrap();
+    let mut reply = Vec::new();
+    FrameCodec::default()
+        .send_message(&mut reply, &ServerMessage::SubscribePlayerResult(SubscribePlayerResult::Ok))
+        .unwrap();
+    let received: ServerMessage = codec.recv_message(&mut Cursor::new(reply)).unwrap();
+
+    let frames = read_recording(&path).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].direction, FrameDirection::Outbound);
+    assert_eq!(frames[1].direction, FrameDirection::Inbound);
+    assert!(frames.iter().all(|frame| frame.player == "dreamy_lemon"));
+    // Chaque ligne contient la trame brute, telle qu'elle a circulé
+    assert_eq!(frames[0].frame.as_bytes(), &buffer[4..]);
+    assert_eq!(serde_json::from_str::<ClientMessage>(&frames[0].frame).unwrap(), sent);
+    assert_eq!(serde_json::from_str::<ServerMessage>(&frames[1].frame).unwrap(), received);
+}
+
+#[test]
+fn each_session_gets_its_own_file() {
+    let directory = record_directory("sessions");
+    // Un codec par partie : la seconde partie du même joueur ne s'ajoute pas à la première
+    for _ in 0..2 {
+        let codec = FrameCodec::for_session(Some(&directory), SessionKind::Player, "dreamy_lemon");
+        codec.send_message(&mut Vec::new(), &ClientMessage::Action(Action::MoveTo(RelativeDirection::Front))).unwrap();
+    }
+    let files: Vec<_> = fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|file| read_recording(file).unwrap().len() == 1));
+
+    // Sans dossier configuré, rien n'est enregistré
+    let codec = FrameCodec::for_session(None, SessionKind::Player, "dreamy_lemon");
+    codec.send_message(&mut Vec::new(), &ClientMessage::Action(Action::MoveTo(RelativeDirection::Front))).unwrap();
+    assert_eq!(fs::read_dir(&directory).unwrap().count(), 2);
+}
+
+#[test]
+fn similar_names_are_recorded_in_different_files() {
+    let directory = record_directory("names");
+    let paths: Vec<_> = [
+        (SessionKind::Player, "a b"),
+        (SessionKind::Player, "a_b"),
+        (SessionKind::Player, "a_20b"),
+        (SessionKind::Player, "team-x"),
+        (SessionKind::Team, "x"),
+        (SessionKind::Player, "../x"),
+    ]
+    .into_iter()
+    .map(|(kind, name)| SessionRecorder::create(&directory, kind, name).unwrap().path().to_path_buf())
+    .collect();
+
+    for (index, path) in paths.iter().enumerate() {
+        assert_eq!(path.parent(), Some(directory.as_path()));
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(paths[..index].iter().all(|other| other.file_name().unwrap() != name), "{}", name);
+    }
+}
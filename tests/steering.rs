@@ -0,0 +1,84 @@
+use sauve_qui_peut::compass_module::CompassReading;
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::player_module::move_player;
+use sauve_qui_peut::protocol_module::RelativeDirection;
+use sauve_qui_peut::radar_module::RadarView;
+
+mod common;
+use common::player;
+
+// Carrefour : devant, à droite, à gauche et derrière sont ouverts.
+// La cellule de droite a plus de passages ouverts que celle de devant.
+fn crossroads() -> RadarView {
+    RadarView::from_ascii(
+        "U U U U U U U
+         U U W O W U U
+         U U U O U O U
+         U O O O O O O
+         U U U O U W U
+         U U W O W U U
+         U U U U U U U",
+    )
+    .unwrap()
+}
+
+#[test]
+fn picks_best_aligned_open_passage() {
+    let radar = crossroads();
+    assert_eq!(move_player(&radar, Some(30.0)), Some(RelativeDirection::Front));
+    assert_eq!(move_player(&radar, Some(60.0)), Some(RelativeDirection::Right));
+    assert_eq!(move_player(&radar, Some(350.0)), Some(RelativeDirection::Front));
+    assert_eq!(move_player(&radar, Some(200.0)), Some(RelativeDirection::Back));
+    assert_eq!(move_player(&radar, Some(250.0)), Some(RelativeDirection::Left));
+    // Sans boussole : main droite
+    assert_eq!(move_player(&radar, None), Some(RelativeDirection::Right));
+}
+
+#[test]
+fn breaks_ties_without_backtracking() {
+    let radar = crossroads();
+    // Entre l'arrière et un côté, ne pas revenir en arrière
+    assert_eq!(move_player(&radar, Some(135.0)), Some(RelativeDirection::Right));
+    assert_eq!(move_player(&radar, Some(225.0)), Some(RelativeDirection::Left));
+    // Entre devant et droite, la cellule de droite mène à plus de passages
+    assert_eq!(move_player(&radar, Some(45.0)), Some(RelativeDirection::Right));
+    // Devant et gauche mènent autant de passages : ordre de la main droite
+    assert_eq!(move_player(&radar, Some(315.0)), Some(RelativeDirection::Front));
+}
+
+#[test]
+fn walled_bearing_falls_back_to_closest_open_passage() {
+    let radar = RadarView::from_ascii(
+        "U U U U U U U
+         U U U U U U U
+         U U U W U U U
+         U U W O O O U
+         U U U O U U U
+         U U U O U U U
+         U U U U U U U",
+    )
+    .unwrap();
+    assert_eq!(move_player(&radar, Some(0.0)), Some(RelativeDirection::Right));
+    assert_eq!(move_player(&radar, Some(60.0)), Some(RelativeDirection::Right));
+    assert_eq!(move_player(&radar, Some(300.0)), Some(RelativeDirection::Back));
+    assert_eq!(move_player(&radar, Some(200.0)), Some(RelativeDirection::Back));
+}
+
+#[test]
+fn exit_bearing_follows_player_heading() {
+    let mut state = player("dreamy_lemon");
+    assert_eq!(state.exit_bearing(), None);
+    state.compass.push(CompassReading { position: (0, 0), heading: Heading::new(Direction::North), angle: 90.0 });
+    assert_eq!(state.exit_bearing(), Some(90.0));
+
+    // Après un pas vers la droite, le joueur regarde vers l'est : la sortie est devant
+    state.tracker.start_move(RelativeDirection::Right);
+    state.tracker.confirm();
+    assert_eq!(state.exit_bearing(), Some(0.0));
+
+    // Avec deux mesures, le cap vise la sortie estimée en (3, 0)
+    state.compass.push(CompassReading { position: (1, 0), heading: Heading::new(Direction::South), angle: 270.0 });
+    state.compass[0] = CompassReading { position: (0, 3), heading: Heading::new(Direction::North), angle: 45.0 };
+    let bearing = state.exit_bearing().unwrap();
+    assert!(bearing.abs() < 0.01 || (360.0 - bearing) < 0.01, "{}", bearing);
+}
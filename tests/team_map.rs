@@ -0,0 +1,158 @@
+use std::{sync::Arc, thread};
+
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::{react, PlayerState};
+use sauve_qui_peut::protocol_module::{ActionError, Hint, ServerMessage};
+use sauve_qui_peut::radar_module::{CellNature, Occupant, RadarCell};
+use sauve_qui_peut::team_map_module::{lock_team_map, SharedTeamMap, TeamMap};
+
+mod common;
+use common::player;
+
+fn maze() -> Maze {
+    let mut maze = Maze::new(3, 3);
+    maze.set_cell(0, 0, RadarCell::Visible { nature: CellNature::Hint, occupant: Occupant::Nobody });
+    maze
+}
+
+fn see(state: &mut PlayerState, maze: &Maze, position: (i32, i32)) {
+    common::see(state, maze, position, Heading::new(Direction::North));
+}
+
+fn teammate(name: &str, team_map: &SharedTeamMap, columns: u32, rows: u32) -> PlayerState {
+    let mut state = player(name).with_team_map(Arc::clone(team_map));
+    react(&mut state, ServerMessage::Hint(Hint::GridSize { columns, rows }));
+    state
+}
+
+#[test]
+fn teammates_share_what_they_see() {
+    let maze = maze();
+    let team_map = TeamMap::shared();
+
+    // Au centre, le premier joueur voit toute la grille et connaît donc sa position
+    let mut first = teammate("curious_broccoli", &team_map, 3, 3);
+    see(&mut first, &maze, (1, 1));
+    assert_eq!(first.team_offset, Some((1, 1)));
+    assert_eq!(lock_team_map(&team_map).map().cells().count(), 9);
+
+    // Le second ne voit pas la première ligne, mais la situe grâce aux passages ouverts
+    let mut second = teammate("dreamy_lemon", &team_map, 3, 3);
+    see(&mut second, &maze, (1, 2));
+    assert_eq!(second.map.grid_offset(), Some((1, 2)));
+    assert_eq!(second.map.cell((-1, -2)), maze.cell(0, 0));
+    assert!(second.map.unexplored().is_empty());
+
+    let team = lock_team_map(&team_map);
+    assert_eq!(team.provenance((0, 0)).unwrap().player, "curious_broccoli");
+    assert_eq!(team.provenance((1, 2)).unwrap().player, "curious_broccoli");
+    drop(team);
+
+    // Le déplacement demandé est bloqué, puis le radar suivant met à jour la provenance des cellules vues
+    react(&mut second, ServerMessage::ActionError(ActionError::CannotPassThroughOpponent));
+    see(&mut second, &maze, (1, 2));
+    let team = lock_team_map(&team_map);
+    assert_eq!(team.provenance((1, 2)).unwrap().player, "dreamy_lemon");
+    assert_eq!(team.provenance((0, 0)).unwrap().player, "curious_broccoli");
+}
+
+#[test]
+fn player_without_grid_position_keeps_its_map_private() {
+    let team_map = TeamMap::shared();
+    // Au milieu d'une grande grille, aucun bord n'est en vue
+    let mut state = teammate("dreamy_lemon", &team_map, 7, 7);
+    see(&mut state, &Maze::new(7, 7), (3, 3));
+    assert_eq!(state.map.grid_offset(), None);
+    assert_eq!(state.team_offset, None);
+    assert_eq!(lock_team_map(&team_map).map().cells().count(), 0);
+}
+
+#[test]
+fn player_is_placed_by_aligning_with_the_team_map() {
+    let maze = Maze::from_ascii(
+        "W W W W W W W W W
+         W O O O W O O O W
+         W O W W W O W O W
+         W O W O O O W O W
+         W O W O W W W O W
+         W O O O W O O O W
+         W W W O W O W O W
+         W O O O O O W O W
+         W O W W W O W O W
+         W O W O O O O O W
+         W O W O W W W O W
+         W O O O W O O O W
+         W W W O W O W W W
+         W O O O O O O O W
+         W W W W W W W W W",
+    )
+    .unwrap();
+    let team_map = TeamMap::shared();
+
+    // Dans le coin nord-ouest, le premier joueur voit deux bords et partage sa carte
+    let mut first = teammate("curious_broccoli", &team_map, 4, 7);
+    see(&mut first, &maze, (0, 0));
+    assert!(first.team_offset.is_some());
+
+    // Le second ne voit que le bord ouest : sa ligne est retrouvée en superposant sa carte à celle de l'équipe
+    let mut second = teammate("dreamy_lemon", &team_map, 4, 7);
+    see(&mut second, &maze, (0, 1));
+    assert_eq!(second.team_offset, Some((0, 1)));
+    assert_eq!(second.map.grid_offset(), Some((0, 1)));
+    assert_eq!(lock_team_map(&team_map).provenance((0, 2)).unwrap().player, "dreamy_lemon");
+}
+
+#[test]
+fn player_with_a_wrong_grid_position_keeps_its_map_private() {
+    let maze = Maze::from_ascii(
+        "W W W W W W W W W W W
+         W O O O O O O O O O W
+         W O W W W W W O W O W
+         W O W O O O W O O O W
+         W O W O W O W O W O W
+         W O W O O O O O O O W
+         W O W O W O W O W O W
+         W O O O O O O O O O W
+         W O W O W O W O W O W
+         W O O O O O O O O O W
+         W W W W W W W W W W W",
+    )
+    .unwrap();
+    let team_map = TeamMap::shared();
+
+    let mut first = teammate("curious_broccoli", &team_map, 5, 5);
+    see(&mut first, &maze, (0, 0));
+    assert_eq!(first.team_offset, Some((0, 0)));
+    let before = lock_team_map(&team_map).map().cells().count();
+
+    // Les murs au nord et à l'ouest cachent des cellules de la grille et passent pour ses bords
+    let mut second = teammate("dreamy_lemon", &team_map, 5, 5);
+    see(&mut second, &maze, (1, 1));
+    assert_eq!(second.map.grid_offset(), Some((0, 0)));
+
+    // La superposition avec la carte de l'équipe contredit ce décalage : rien n'est versé
+    assert_eq!(second.team_offset, None);
+    let team = lock_team_map(&team_map);
+    assert_eq!(team.map().cells().count(), before);
+    assert!(team.map().cells().all(|(position, _)| team.provenance(position).unwrap().player == "curious_broccoli"));
+}
+
+#[test]
+fn players_in_threads_write_to_the_same_map() {
+    let maze = maze();
+    let team_map = TeamMap::shared();
+    let players: Vec<_> = ["a", "b", "c"]
+        .into_iter()
+        .map(|name| {
+            let (maze, mut state) = (maze.clone(), teammate(name, &team_map, 3, 3));
+            thread::spawn(move || see(&mut state, &maze, (1, 1)))
+        })
+        .collect();
+    for handle in players {
+        handle.join().unwrap();
+    }
+    let team = lock_team_map(&team_map);
+    assert_eq!(team.map().cells().count(), 9);
+    assert!(["a", "b", "c"].contains(&team.provenance((1, 1)).unwrap().player.as_str()));
+}
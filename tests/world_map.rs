@@ -0,0 +1,245 @@
+use sauve_qui_peut::direction_module::{Direction, Heading};
+use sauve_qui_peut::map_module::{GridOffsets, WorldMap, MAX_GRID_SIZE};
+use sauve_qui_peut::maze_module::Maze;
+use sauve_qui_peut::player_module::decoder;
+use sauve_qui_peut::radar_module::{Passage, RadarCell, RadarView};
+
+fn maze() -> Maze {
+    Maze::from_ascii(
+        "W W W W W W W W W
+         W O O O W O O E W
+         W O W O W O W W W
+         W O W O O O O O W
+         W O W W W O W W W
+         W O O O O O O O W
+         W W W W W W W W W",
+    )
+    .unwrap()
+}
+
+#[test]
+fn merged_radars_match_the_maze() {
+    let maze = maze();
+    let mut map = WorldMap::new();
+    // Le joueur part de (0, 2) tourné vers le nord, puis se retrouve en (2, 2) tourné vers l'est
+    map.merge(&maze.radar_view_facing(0, 2, Heading::new(Direction::North)), (0, 2), Heading::new(Direction::North));
+    map.merge(&maze.radar_view_facing(2, 2, Heading::new(Direction::East)), (2, 2), Heading::new(Direction::East));
+
+    for position in [(0, 1), (1, 1), (2, 1), (3, 1), (0, 2), (1, 2), (2, 2), (3, 2)] {
+        assert!(map.is_known(position), "{:?}", position);
+        assert_eq!(map.cell(position), maze.cell(position.0, position.1), "{:?}", position);
+        for direction in Direction::ALL {
+            let expected = maze.passage(position.0, position.1, direction);
+            assert_eq!(map.passage(position, direction), expected, "{:?} {:?}", position, direction);
+        }
+    }
+    assert!(!map.is_known((0, 0)));
+    assert_eq!(map.cell((3, 0)), RadarCell::Undefined);
+    assert_eq!(map.passage((0, 0), Direction::North), Passage::Undefined);
+    assert!(map.is_open((2, 2), Direction::East));
+    assert!(!map.is_open((2, 2), Direction::South));
+
+    let mut neighbors: Vec<_> = map.neighbors((2, 2)).collect();
+    neighbors.sort_by_key(|(_, position)| *position);
+    assert_eq!(neighbors, vec![(Direction::West, (1, 2)), (Direction::North, (2, 1)), (Direction::East, (3, 2))]);
+    assert_eq!(map.unexplored(), vec![(0, 0), (1, 0), (2, 0)]);
+    assert_eq!(map.exits().count(), 0);
+}
+
+#[test]
+fn records_exits_and_keeps_known_cells() {
+    let maze = maze();
+    let mut map = WorldMap::new();
+    map.merge(&maze.radar_view_facing(3, 0, Heading::new(Direction::South)), (3, 0), Heading::new(Direction::South));
+    assert_eq!(map.exits().collect::<Vec<_>>(), vec![(3, 0)]);
+    // Un radar vu d'ailleurs ne fait pas oublier ce qui est déjà connu
+    map.merge(&maze.radar_view(1, 2), (1, 2), Heading::default());
+    assert!(map.cell((3, 0)).is_goal());
+    assert!(map.to_string().contains('E'));
+    assert_eq!(WorldMap::new().bounds(), None);
+}
+
+#[test]
+fn grid_size_bounds_the_map() {
+    let maze = maze();
+    let start = (0, 2);
+    let heading = Heading::new(Direction::North);
+    let mut map = WorldMap::new();
+    assert!(map.may_be_inside((-100, 100)));
+    assert_eq!(map.grid_offset(), None);
+
+    map.set_grid_size(4, 3).unwrap();
+    map.merge(&maze.radar_view_facing(start.0, start.1, heading), (0, 0), heading);
+    // Le joueur part du coin sud-ouest : le bord ouest fixe x. La carte entre encore dans la grille
+    // couchée comme debout, la hauteur n'est donc pas connue et le bord sud ne fixe pas y.
+    assert_eq!(map.grid_spans(), None);
+    let offsets = map.grid_offsets().unwrap();
+    assert_eq!(offsets, GridOffsets { x: (start.0, start.0), y: (start.1, start.1 + 1) });
+    assert_eq!(map.grid_offset(), None);
+    assert!(!map.may_be_inside((-4, 0)));
+    assert!(!map.may_be_inside((0, 3)));
+    assert!(!map.may_be_inside((-1, 0)));
+    assert!(map.may_be_inside((3, -2)));
+
+    for y in 0..3 {
+        for x in 0..4 {
+            map.merge(&maze.radar_view_facing(x, y, heading), (x - start.0, y - start.1), heading);
+        }
+    }
+    assert_eq!(map.grid_spans(), Some((4, 3)));
+    assert_eq!(map.grid_offset(), Some(start));
+    assert_eq!(map.grid_position((3, -2)), Some((3, 0)));
+    assert!(map.unexplored().is_empty());
+    assert!(!map.may_be_inside((4, 0)));
+}
+
+#[test]
+fn unreasonable_grid_sizes_are_rejected() {
+    let mut map = WorldMap::new();
+    for (columns, rows) in [(0, 3), (4, 0), (MAX_GRID_SIZE + 1, 3), (4, u32::MAX)] {
+        assert!(map.set_grid_size(columns, rows).is_err(), "{}x{}", columns, rows);
+    }
+    assert_eq!(map.grid_size(), None);
+
+    map.set_grid_size(MAX_GRID_SIZE, MAX_GRID_SIZE).unwrap();
+    assert_eq!(map.grid_size(), Some((MAX_GRID_SIZE, MAX_GRID_SIZE)));
+}
+
+#[test]
+fn each_border_fixes_its_own_axis() {
+    let maze = Maze::from_ascii(
+        "W W W W W W W W W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W O O O O O O O W
+         W W W W W W W W W",
+    )
+    .unwrap();
+
+    // Seul le bord ouest est en vue : x est connu, y ne l'est pas encore
+    let start = (0, 3);
+    let heading = Heading::new(Direction::North);
+    let mut map = WorldMap::new();
+    map.set_grid_size(4, 7).unwrap();
+    map.merge(&maze.radar_view_facing(start.0, start.1, heading), (0, 0), heading);
+    let offsets = map.grid_offsets().unwrap();
+    assert_eq!(offsets.fixed_x(), Some(start.0));
+    assert_eq!(offsets.fixed_y(), None);
+    assert_eq!(offsets.y, (2, 4));
+    assert_eq!(map.grid_offset(), None);
+
+    // Un radar reçu plus au nord, tourné vers l'est, montre le bord nord : y est connu à son tour
+    let heading = Heading::new(Direction::East);
+    map.merge(&maze.radar_view_facing(1, 0, heading), (1, -3), heading);
+    assert_eq!(map.grid_offset(), Some(start));
+
+    // Le bord est suffit aussi, même sans avoir vu la carte sur toute sa largeur
+    let mut map = WorldMap::new();
+    map.set_grid_size(4, 7).unwrap();
+    map.merge(&maze.radar_view_facing(3, 3, heading), (0, 0), heading);
+    assert_eq!(map.grid_offsets().unwrap().fixed_x(), Some(3));
+}
+
+#[test]
+fn ambiguous_alignment_is_refused() {
+    // Dans une grille sans murs intérieurs, tous les décalages verticaux se ressemblent
+    let maze = Maze::new(4, 7);
+    let heading = Heading::new(Direction::North);
+    let mut reference = WorldMap::new();
+    reference.merge(&maze.radar_view(0, 0), (0, 0), heading);
+    reference.merge(&maze.radar_view(0, 3), (0, 3), heading);
+
+    let mut map = WorldMap::new();
+    map.set_grid_size(4, 7).unwrap();
+    map.merge(&maze.radar_view_facing(0, 2, heading), (0, 0), heading);
+    assert_eq!(map.grid_offsets().unwrap().fixed_x(), Some(0));
+    assert_eq!(map.align_with(&reference), None);
+
+    // Un seul décalage possible sur chaque axe : la superposition le confirme
+    map.merge(&maze.radar_view_facing(0, 0, heading), (0, -2), heading);
+    assert_eq!(map.align_with(&reference), Some((0, 2)));
+}
+
+#[test]
+fn walls_hiding_cells_of_the_server_radar_are_not_borders() {
+    // Le radar du serveur cache aussi des cellules de la grille : au nord-ouest derrière deux murs,
+    // à l'est et au sud derrière un mur, alors que les cellules voisines sont visibles
+    let radar = decoder("ieysGjGO8papd/a").unwrap();
+    let heading = Heading::new(Direction::North);
+    let mut map = WorldMap::new();
+    map.set_grid_size(10, 10).unwrap();
+    map.merge(&radar, (0, 0), heading);
+
+    for (position, _) in map.cells() {
+        assert!(map.may_be_inside(position), "{:?}", position);
+    }
+    let offsets = map.grid_offsets().unwrap();
+    assert_eq!(offsets.fixed_y(), None, "{:?}", offsets);
+    assert_ne!(offsets.fixed_x(), Some(0), "{:?}", offsets);
+    assert_eq!(map.grid_offset(), None);
+
+    // Le résultat ne dépend pas de l'ordre des radars
+    let mut other = WorldMap::new();
+    other.set_grid_size(10, 10).unwrap();
+    let moved = decoder("ieysGjGO8papd/a").unwrap();
+    other.merge(&moved, (3, 4), heading);
+    other.merge(&radar, (0, 0), heading);
+    map.merge(&moved, (3, 4), heading);
+    assert_eq!(map.grid_offsets(), other.grid_offsets());
+}
+
+#[test]
+fn contradictory_borders_are_dropped() {
+    let west = RadarView::from_ascii(
+        "U U U U U U U
+         U U U U U U U
+         U U U U U U U
+         U U W O U U U
+         U U U U U U U
+         U U U U U U U
+         U U U U U U U",
+    )
+    .unwrap();
+    let east = RadarView::from_ascii(
+        "U U U U U U U
+         U U U U U U U
+         U U U U U U U
+         U U U O W U U
+         U U U U U U U
+         U U U U U U U
+         U U U U U U U",
+    )
+    .unwrap();
+    let heading = Heading::new(Direction::North);
+    let mut map = WorldMap::new();
+    map.set_grid_size(10, 10).unwrap();
+
+    // Chaque bord seul fixe x
+    map.merge(&west, (0, 0), heading);
+    assert_eq!(map.grid_offsets().unwrap().fixed_x(), Some(0));
+    let mut alone = WorldMap::new();
+    alone.set_grid_size(10, 10).unwrap();
+    alone.merge(&east, (5, 0), heading);
+    assert_eq!(alone.grid_offsets().unwrap().fixed_x(), Some(4));
+
+    // Les deux ensemble donnent une grille de 6 colonnes au lieu de 10 : aucun n'est retenu
+    map.merge(&east, (5, 0), heading);
+    assert_eq!(map.grid_offsets().unwrap().x, (0, 4));
+
+    // Un candidat démenti par une cellule vue au-delà est écarté
+    let mut map = WorldMap::new();
+    map.set_grid_size(10, 10).unwrap();
+    map.merge(&west, (0, 0), heading);
+    map.merge(&east, (-1, 0), heading);
+    assert_eq!(map.grid_offsets().unwrap().fixed_x(), None);
+}